@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{
+            transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Account as Token2022Account,
+    },
+    Mint, TokenAccount,
+};
+use indie_games::state::asset_state::AssetData;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("39xrwBwQBHwaanFLctMeiZaT5AZ75sQhFuboXx4wGQ3p");
+
+#[program]
+pub mod asset_transfer_hook {
+    use super::*;
+
+    // pins the asset data account as the one extra account every transfer resolves
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        let extra_account_metas = [ExtraAccountMeta::new_with_pubkey(
+            &ctx.accounts.asset_account.key(),
+            false,
+            false,
+        )?];
+        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas)?;
+        msg!("transfer hook set up for {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+        let source_info = ctx.accounts.source_token.to_account_info();
+        {
+            let source_data = source_info.try_borrow_data()?;
+            let source = StateWithExtensions::<Token2022Account>::unpack(&source_data)?;
+            let hook_state = source.get_extension::<TransferHookAccount>()?;
+            require!(
+                bool::from(hook_state.transferring),
+                TransferHookError::NotTransferring
+            );
+        }
+        require!(
+            ctx.accounts.asset_account.trade,
+            TransferHookError::AssetNotTradable
+        );
+        // holdings and escrows are owned by indie-games PDAs, so a transfer signed by
+        // anything else skipped the marketplace and the royalties it pays out
+        let authority = &ctx.accounts.owner;
+        let (escrow_authority, _) =
+            Pubkey::find_program_address(&[source_info.key.as_ref()], &indie_games::ID);
+        require!(
+            authority.owner == &indie_games::ID || authority.key() == escrow_authority,
+            TransferHookError::TransferNotRouted
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    /// CHECK: TLV account written by the handler
+    #[account(
+        init,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+        space = ExtraAccountMetaList::size_of(1)?,
+        payer = payer
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    #[account(
+        seeds = [asset_account.game.as_ref(), asset_account.key().as_ref()],
+        bump,
+        seeds::program = indie_games::ID
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub asset_account: Account<'info, AssetData>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// account order is fixed by the transfer hook interface
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [asset_account.game.as_ref(), asset_account.key().as_ref()],
+        bump,
+        seeds::program = indie_games::ID
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: owner or delegate of the source account, checked in the handler
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: validated by seeds
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub asset_account: Account<'info, AssetData>,
+}
+
+#[error_code]
+pub enum TransferHookError {
+    #[msg("Hook was not invoked by a token transfer")]
+    NotTransferring,
+    #[msg("Asset is not tradable")]
+    AssetNotTradable,
+    #[msg("Asset transfers must go through indie-games")]
+    TransferNotRouted,
+}
@@ -8,10 +8,32 @@ pub enum AssetErrors {
     InvalidContext,
     #[msg("Minting new token failed")]
     MintFailed,
-    #[msg("Only Game Owner can Create new Assets")]
+    #[msg("Signer is not authorized for this operation")]
     InvalidOperation,
     #[msg("Given Game Account and Asset Account are not related")]
     InvalidGameOrAssetAccount,
     #[msg("Cannot transfer non-tradable Assets")]
     InvalidTransfer,
+    #[msg("Royalty basis points exceed 100%")]
+    InvalidRoyalty,
+    #[msg("Only soulbound assets can be clawed back")]
+    NotSoulbound,
+    #[msg("Minting would exceed the asset's max supply")]
+    SupplyCapExceeded,
+    #[msg("Minting would exceed the per-wallet cap")]
+    WalletCapExceeded,
+    #[msg("Asset supply is locked")]
+    SupplyLocked,
+    #[msg("Collateral accounts are missing or invalid")]
+    InvalidCollateralAccount,
+    #[msg("Collateral redemption exceeds deposits")]
+    CollateralUnderflow,
+    #[msg("Collateral computation overflowed")]
+    CollateralOverflow,
+    #[msg("Asset is under-collateralized, minting is paused")]
+    AssetUnhealthy,
+    #[msg("Asset collateral is healthy, nothing to liquidate")]
+    AssetHealthy,
+    #[msg("Royalty-enforced assets only change hands through the marketplace")]
+    RoyaltyEnforced,
 }
@@ -5,5 +5,7 @@ pub enum GameErrors {
     #[msg("Calle is not the owner")]
     UnAuthorizedOperation,
     #[msg("args check failed")]
-    InvalidArgs
-}
\ No newline at end of file
+    InvalidArgs,
+    #[msg("No ownership transfer is pending for this game")]
+    NoPendingOwner,
+}
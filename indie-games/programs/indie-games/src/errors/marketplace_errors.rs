@@ -11,5 +11,47 @@ pub enum MarketplaceError {
     #[msg("Asset trding is disbled for this asset")]
     CantListAsset,
     #[msg("Seller is not the authorized of the ata")]
-    NotAuthorized
-}
\ No newline at end of file
+    NotAuthorized,
+    #[msg("Sale price must be greater than zero")]
+    InvalidPrice,
+    #[msg("Quantity must be greater than zero")]
+    InvalidQuantity,
+    #[msg("Requested quantity exceeds what is left in the listing")]
+    QuantityExceedsListing,
+    #[msg("Fee basis points exceed 100%")]
+    InvalidFee,
+    #[msg("Payment account does not match the listing or marketplace config")]
+    InvalidPaymentAccount,
+    #[msg("Auction start and end times are invalid")]
+    InvalidAuctionWindow,
+    #[msg("Auction is not accepting bids")]
+    AuctionNotActive,
+    #[msg("Auction has not ended yet")]
+    AuctionNotEnded,
+    #[msg("Bid is below the reserve price or minimum increment")]
+    BidTooLow,
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+    #[msg("Operation is not supported for this listing type")]
+    InvalidListingType,
+    #[msg("Current price is above the buyer's max price")]
+    PriceAboveMax,
+    #[msg("Offer has expired")]
+    OfferExpired,
+    #[msg("Offer has not expired yet")]
+    OfferNotExpired,
+    #[msg("Swap bundle is empty, too large or has duplicate assets")]
+    InvalidSwapBundle,
+    #[msg("Passed accounts do not match the swap bundle")]
+    SwapAccountsMismatch,
+    #[msg("Listing has expired")]
+    ListingExpired,
+    #[msg("Listing has not expired yet")]
+    ListingNotExpired,
+    #[msg("Listing is not allowed by the marketplace policy")]
+    ListingPolicyViolation,
+    #[msg("Marketplace config is invalid")]
+    InvalidMarketplaceConfig,
+    #[msg("Payment mint is not accepted by the marketplace")]
+    PaymentMintNotAllowed,
+}
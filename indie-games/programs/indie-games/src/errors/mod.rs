@@ -1,3 +1,4 @@
 pub mod asset_errors;
 pub mod game_errors;
-pub mod marketplace_errors;
\ No newline at end of file
+pub mod marketplace_errors;
+pub mod oracle_errors;
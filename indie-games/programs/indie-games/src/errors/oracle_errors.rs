@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Signer can't manage this asset's price feed")]
+    NotAuthorized,
+    #[msg("Price feed doesn't price this asset or isn't run by its game owner")]
+    FeedMismatch,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Asset is priced by a feed that was not passed")]
+    MissingPriceFeed,
+}
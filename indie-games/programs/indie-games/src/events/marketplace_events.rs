@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct SaleSettled {
+    pub listing_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub quantity: u64,
+    pub total_price: u64,
+    pub protocol_fee: u64,
+    pub royalty: u64,
+    pub seller_proceeds: u64,
+}
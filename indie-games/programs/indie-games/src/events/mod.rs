@@ -0,0 +1 @@
+pub mod marketplace_events;
@@ -0,0 +1,546 @@
+use crate::{
+    errors::marketplace_errors::*,
+    events::marketplace_events::*,
+    instructions::{
+        asset_management_instructions::transfer_asset,
+        asset_marketplace_instructions::pay_sale_proceeds,
+    },
+    state::{asset_state::*, auction_state::*, marketplace_state::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer as SPLTransfer,
+    },
+    token_interface::{
+        Mint as AssetMint, TokenAccount as AssetTokenAccount, TokenInterface as AssetTokenInterface,
+    },
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct CreateAuctionArgs {
+    pub asset_name: String,
+    pub asset_game_id: Pubkey,
+    pub amount: u64,
+    pub reserve_price: u64,
+    pub min_bid_increment: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+pub fn create_auction_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateAuctionContext<'info>>,
+    args: CreateAuctionArgs,
+) -> Result<()> {
+    require!(
+        ctx.accounts.asset_account.trade,
+        MarketplaceError::CantListAsset
+    );
+    require!(args.amount > 0, MarketplaceError::InvalidQuantity);
+    require!(args.reserve_price > 0, MarketplaceError::InvalidPrice);
+    // a zero increment would let a bid matching the top one take the lead
+    require!(args.min_bid_increment > 0, MarketplaceError::InvalidPrice);
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        args.start_time < args.end_time && args.end_time > now,
+        MarketplaceError::InvalidAuctionWindow
+    );
+    let seller = ctx.accounts.seller.key();
+    let seller_ata = &ctx.accounts.seller_asset_ata;
+    let seller_ata_authority = &ctx.accounts.seller_asset_ata_authority;
+    require!(
+        seller_ata_authority.user == seller && seller_ata.owner == seller_ata_authority.key(),
+        MarketplaceError::NotAuthorized
+    );
+
+    let market = &mut ctx.accounts.marketplace;
+    let auction = &mut ctx.accounts.auction;
+    auction.auction_id = market.current_listing_id;
+    auction.seller = seller;
+    auction.asset_account = ctx.accounts.asset_account.key();
+    auction.asset_mint = ctx.accounts.asset_mint.key();
+    auction.escrow_ata = ctx.accounts.market_asset_ata.key();
+    auction.seller_asset_ata = seller_ata.key();
+    auction.dsc_credit_ata = ctx.accounts.seller_dsc_ata.key();
+    auction.amount = args.amount;
+    auction.reserve_price = args.reserve_price;
+    auction.min_bid_increment = args.min_bid_increment;
+    auction.start_time = args.start_time;
+    auction.end_time = args.end_time;
+    auction.highest_bid = 0;
+    auction.highest_bidder = None;
+    auction.bump = ctx.bumps.auction;
+    market.current_listing_id = market
+        .current_listing_id
+        .checked_add(1)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+
+    let mint_key = ctx.accounts.asset_mint.key();
+    let seeds: &[&[&[u8]]] = &[&[
+        seller.as_ref(),
+        mint_key.as_ref(),
+        &[ctx.bumps.seller_asset_ata_authority],
+    ]];
+    transfer_asset(
+        ctx.accounts.asset_token_program.to_account_info(),
+        seller_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.market_asset_ata.to_account_info(),
+        seller_ata_authority.to_account_info(),
+        ctx.remaining_accounts,
+        seeds,
+        args.amount,
+    )?;
+    msg!("auction {} created", auction.auction_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: CreateAuctionArgs)]
+pub struct CreateAuctionContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        init,
+        seeds = [b"auction", marketplace.current_listing_id.to_le_bytes().as_ref()],
+        bump,
+        payer = seller,
+        space = 8 + Auction::INIT_SPACE
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(
+        seeds = [args.asset_name.as_bytes(), args.asset_game_id.as_ref()],
+        bump,
+    )]
+    pub asset_account: Box<Account<'info, AssetData>>,
+    #[account(
+        seeds = [args.asset_game_id.as_ref(), asset_account.key().as_ref()],
+        bump,
+        mint::token_program = asset_token_program,
+    )]
+    pub asset_mint: Box<InterfaceAccount<'info, AssetMint>>,
+    #[account(
+        mut,
+        token::mint = asset_mint,
+        token::authority = market_asset_ata_authority,
+        token::token_program = asset_token_program,
+    )]
+    pub market_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    /// CHECK: PDA signer of the escrow account, holds no data
+    #[account(
+        seeds = [market_asset_ata.key().as_ref()],
+        bump
+    )]
+    pub market_asset_ata_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = asset_mint,
+    )]
+    pub seller_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        seeds = [seller.key().as_ref(), asset_mint.key().as_ref()],
+        bump
+    )]
+    pub seller_asset_ata_authority: Box<Account<'info, AssetAuthority>>,
+    #[account(
+        seeds = [b"mint"],
+        bump,
+        seeds::program = stable_coin::ID
+    )]
+    pub dsc_mint: Box<Account<'info, Mint>>,
+    // where the winning bid is paid out, checked now so settlement can't get stuck on it
+    #[account(
+        token::mint = dsc_mint,
+        token::authority = seller,
+    )]
+    pub seller_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        seeds = [b"bid_vault", auction.key().as_ref()],
+        bump,
+        payer = seller,
+        token::mint = dsc_mint,
+        token::authority = auction,
+    )]
+    pub bid_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct PlaceBidArgs {
+    pub amount: u64,
+}
+
+pub fn place_bid_handler(ctx: Context<PlaceBidContext>, args: PlaceBidArgs) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let bidder = ctx.accounts.bidder.key();
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= auction.start_time && now < auction.end_time,
+        MarketplaceError::AuctionNotActive
+    );
+    require!(bidder != auction.seller, MarketplaceError::NotAuthorized);
+    let min_bid = match auction.highest_bidder {
+        None => auction.reserve_price,
+        Some(_) => auction
+            .highest_bid
+            .checked_add(auction.min_bid_increment)
+            .ok_or(MarketplaceError::ArithmeticError)?,
+    };
+    require!(args.amount >= min_bid, MarketplaceError::BidTooLow);
+
+    // the outbid bidder takes the whole vault balance, so nothing sent to it can pile up.
+    // It goes to their canonical ATA, recreated here if they closed it to block the refund
+    if auction.highest_bidder.is_some() {
+        let (Some(_), Some(previous_bidder_dsc_ata)) = (
+            ctx.accounts.previous_bidder.as_ref(),
+            ctx.accounts.previous_bidder_dsc_ata.as_ref(),
+        ) else {
+            return err!(MarketplaceError::InvalidPaymentAccount);
+        };
+        let auction_id = auction.auction_id.to_le_bytes();
+        let seeds: &[&[&[u8]]] = &[&[b"auction", auction_id.as_ref(), &[auction.bump]]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_accounts = SPLTransfer {
+            from: ctx.accounts.bid_vault.to_account_info(),
+            to: previous_bidder_dsc_ata.to_account_info(),
+            authority: auction.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds);
+        transfer(cpi_ctx, ctx.accounts.bid_vault.amount)?;
+    }
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_accounts = SPLTransfer {
+        from: ctx.accounts.bidder_dsc_ata.to_account_info(),
+        to: ctx.accounts.bid_vault.to_account_info(),
+        authority: ctx.accounts.bidder.to_account_info(),
+    };
+    transfer(CpiContext::new(cpi_program, cpi_accounts), args.amount)?;
+
+    let auction = &mut ctx.accounts.auction;
+    auction.highest_bid = args.amount;
+    auction.highest_bidder = Some(bidder);
+    auction.highest_bidder_asset_ata = ctx.accounts.bidder_asset_ata.key();
+    msg!("bid of {} on auction {}", args.amount, auction.auction_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PlaceBidContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", auction.auction_id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(
+        mut,
+        seeds = [b"bid_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub bid_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"mint"],
+        bump,
+        seeds::program = stable_coin::ID
+    )]
+    pub dsc_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = dsc_mint,
+        associated_token::authority = bidder,
+    )]
+    pub bidder_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(token::mint = auction.asset_mint)]
+    pub bidder_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    // only needed once the auction has a top bid to refund
+    /// CHECK: wallet of the bidder being outbid, only owns the refund account
+    #[account(address = auction.highest_bidder.unwrap_or_default() @ MarketplaceError::InvalidPaymentAccount)]
+    pub previous_bidder: Option<AccountInfo<'info>>,
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = dsc_mint,
+        associated_token::authority = previous_bidder,
+    )]
+    pub previous_bidder_dsc_ata: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// returns the escrowed asset and closes the bid vault, shared by settle and cancel.
+// `leftover` is whatever the vault still holds once the winning bid is paid out, it
+// goes to the seller's DSC account since the vault can only close empty
+#[allow(clippy::too_many_arguments)]
+fn release_auction_escrow<'info>(
+    auction: &Account<'info, Auction>,
+    token_program: AccountInfo<'info>,
+    asset_token_program: AccountInfo<'info>,
+    market_asset_ata: AccountInfo<'info>,
+    asset_mint: &InterfaceAccount<'info, AssetMint>,
+    market_asset_ata_authority: AccountInfo<'info>,
+    escrow_bump: u8,
+    asset_destination_ata: AccountInfo<'info>,
+    hook_accounts: &[AccountInfo<'info>],
+    bid_vault: AccountInfo<'info>,
+    seller_dsc_ata: AccountInfo<'info>,
+    leftover: u64,
+    seller: AccountInfo<'info>,
+) -> Result<()> {
+    let escrow_ata = market_asset_ata.key();
+    let escrow_seeds: &[&[&[u8]]] = &[&[escrow_ata.as_ref(), &[escrow_bump]]];
+    transfer_asset(
+        asset_token_program,
+        market_asset_ata,
+        asset_mint,
+        asset_destination_ata,
+        market_asset_ata_authority,
+        hook_accounts,
+        escrow_seeds,
+        auction.amount,
+    )?;
+
+    let auction_id = auction.auction_id.to_le_bytes();
+    let auction_seeds: &[&[&[u8]]] = &[&[b"auction", auction_id.as_ref(), &[auction.bump]]];
+    if leftover > 0 {
+        let cpi_accounts = SPLTransfer {
+            from: bid_vault.clone(),
+            to: seller_dsc_ata,
+            authority: auction.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(token_program.clone(), cpi_accounts, auction_seeds);
+        transfer(cpi_ctx, leftover)?;
+    }
+    let cpi_accounts = CloseAccount {
+        account: bid_vault,
+        destination: seller,
+        authority: auction.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, auction_seeds);
+    close_account(cpi_ctx)
+}
+
+pub fn settle_auction_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleAuctionContext<'info>>,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= auction.end_time, MarketplaceError::AuctionNotEnded);
+    let mut leftover = ctx.accounts.bid_vault.amount;
+
+    if let Some(winner) = auction.highest_bidder {
+        let proceeds = split_sale_proceeds(
+            auction.highest_bid,
+            ctx.accounts.marketplace.fee_bps,
+            ctx.accounts.asset_account.royalty_bps,
+        )
+        .ok_or(MarketplaceError::ArithmeticError)?;
+        let auction_id = auction.auction_id.to_le_bytes();
+        let seeds: &[&[&[u8]]] = &[&[b"auction", auction_id.as_ref(), &[auction.bump]]];
+        pay_sale_proceeds(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.bid_vault.to_account_info(),
+            auction.to_account_info(),
+            seeds,
+            ctx.accounts.seller_dsc_ata.to_account_info(),
+            ctx.accounts.fee_treasury_ata.to_account_info(),
+            Some(ctx.accounts.royalty_ata.to_account_info()),
+            &proceeds,
+        )?;
+        leftover = leftover.saturating_sub(auction.highest_bid);
+        emit!(SaleSettled {
+            listing_id: auction.auction_id,
+            buyer: winner,
+            seller: auction.seller,
+            quantity: auction.amount,
+            total_price: auction.highest_bid,
+            protocol_fee: proceeds.protocol_fee,
+            royalty: proceeds.royalty,
+            seller_proceeds: proceeds.seller_proceeds,
+        });
+    }
+
+    release_auction_escrow(
+        auction,
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.asset_token_program.to_account_info(),
+        ctx.accounts.market_asset_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.market_asset_ata_authority.to_account_info(),
+        ctx.bumps.market_asset_ata_authority,
+        ctx.accounts.asset_destination_ata.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.bid_vault.to_account_info(),
+        ctx.accounts.seller_dsc_ata.to_account_info(),
+        leftover,
+        ctx.accounts.seller.to_account_info(),
+    )?;
+    msg!("auction {} settled", auction.auction_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleAuctionContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", auction.auction_id.to_le_bytes().as_ref()],
+        bump = auction.bump,
+        close = seller
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>,
+    #[account(address = auction.asset_account)]
+    pub asset_account: Box<Account<'info, AssetData>>,
+    #[account(
+        mut,
+        address = auction.escrow_ata @ MarketplaceError::SaleNotFound
+    )]
+    pub market_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        address = auction.asset_mint,
+        mint::token_program = asset_token_program,
+    )]
+    pub asset_mint: Box<InterfaceAccount<'info, AssetMint>>,
+    /// CHECK: PDA signer of the escrow account, holds no data
+    #[account(
+        seeds = [market_asset_ata.key().as_ref()],
+        bump
+    )]
+    pub market_asset_ata_authority: AccountInfo<'info>,
+    // the winner's asset account, or the seller's when nobody met the reserve
+    #[account(
+        mut,
+        constraint = asset_destination_ata.key() == if auction.highest_bidder.is_some() {
+            auction.highest_bidder_asset_ata
+        } else {
+            auction.seller_asset_ata
+        } @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub asset_destination_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"bid_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub bid_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = auction.dsc_credit_ata @ MarketplaceError::InvalidPaymentAccount,
+        token::mint = bid_vault.mint,
+    )]
+    pub seller_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = bid_vault.mint,
+        constraint = fee_treasury_ata.owner == marketplace.fee_treasury @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub fee_treasury_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = bid_vault.mint,
+        constraint = royalty_ata.owner == asset_account.royalty_recipient @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub royalty_ata: Box<Account<'info, TokenAccount>>,
+    /// CHECK: receives the auction and vault rent
+    #[account(mut, address = auction.seller @ MarketplaceError::NotAuthorized)]
+    pub seller: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+}
+
+pub fn cancel_auction_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelAuctionContext<'info>>,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    require!(
+        auction.seller == ctx.accounts.seller.key(),
+        MarketplaceError::NotAuthorized
+    );
+    require!(
+        auction.highest_bidder.is_none(),
+        MarketplaceError::AuctionHasBids
+    );
+    release_auction_escrow(
+        auction,
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.asset_token_program.to_account_info(),
+        ctx.accounts.market_asset_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.market_asset_ata_authority.to_account_info(),
+        ctx.bumps.market_asset_ata_authority,
+        ctx.accounts.seller_asset_ata.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.bid_vault.to_account_info(),
+        ctx.accounts.seller_dsc_ata.to_account_info(),
+        ctx.accounts.bid_vault.amount,
+        ctx.accounts.seller.to_account_info(),
+    )?;
+    msg!("auction {} cancelled", auction.auction_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelAuctionContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", auction.auction_id.to_le_bytes().as_ref()],
+        bump = auction.bump,
+        close = seller
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(
+        mut,
+        address = auction.escrow_ata @ MarketplaceError::SaleNotFound
+    )]
+    pub market_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        address = auction.asset_mint,
+        mint::token_program = asset_token_program,
+    )]
+    pub asset_mint: Box<InterfaceAccount<'info, AssetMint>>,
+    /// CHECK: PDA signer of the escrow account, holds no data
+    #[account(
+        seeds = [market_asset_ata.key().as_ref()],
+        bump
+    )]
+    pub market_asset_ata_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = auction.seller_asset_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub seller_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"bid_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub bid_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = auction.dsc_credit_ata @ MarketplaceError::InvalidPaymentAccount,
+        token::mint = bid_vault.mint,
+    )]
+    pub seller_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+}
@@ -1,10 +1,21 @@
 use crate::errors::asset_errors::*;
-use crate::state::{asset_state::*, game_state::*};
+use crate::instructions::price_feed_instructions::fresh_price_feed;
+use crate::state::{asset_state::*, game_state::*, marketplace_state::MAX_BPS};
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer as DSC_Transfer},
+    token::{
+        transfer, Mint as DscMint, Token, TokenAccount as DscTokenAccount, Transfer as DSC_Transfer,
+    },
+    token_2022::spl_token_2022::{extension::ExtensionType, state::Mint as MintState},
+    token_interface::{
+        initialize_mint2, mint_to, non_transferable_mint_initialize, permanent_delegate_initialize,
+        transfer_hook_initialize, InitializeMint2, Mint, MintTo, NonTransferableMintInitialize,
+        PermanentDelegateInitialize, TokenAccount, TokenInterface, TransferHookInitialize,
+    },
 };
+use stable_coin::state::oracle_state::PriceFeed;
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct InitializeAssetDataArgs {
@@ -17,12 +28,174 @@ pub struct InitializeAssetDataArgs {
     trade_option: bool,
     collateral_option: bool,
     collateral_ratio: u64,
+    royalty_bps: u16,
+    royalty_recipient: Pubkey,
+    royalty_enforced: bool,
+    max_supply: Option<u64>,
+    max_per_wallet: Option<u64>,
+}
+
+// pulls the collateral for a fresh mint into the asset's vault and books it
+pub(crate) fn deposit_asset_collateral<'info>(
+    asset: &mut AssetData,
+    amount: u64,
+    token_program: AccountInfo<'info>,
+    user_dsc_ata: Option<&Account<'info, DscTokenAccount>>,
+    collateral_vault: Option<&Account<'info, DscTokenAccount>>,
+    price_feed: Option<&Account<'info, PriceFeed>>,
+    user: AccountInfo<'info>,
+) -> Result<()> {
+    if !asset.collateral_option {
+        return Ok(());
+    }
+    let feed = fresh_price_feed(asset, price_feed)?;
+    // sized against the top of the feed's confidence band
+    let price = match feed {
+        Some(feed) => feed
+            .price
+            .checked_add(feed.confidence)
+            .ok_or(AssetErrors::CollateralOverflow)?,
+        None => asset.base_price().ok_or(AssetErrors::CollateralOverflow)?,
+    };
+    let (Some(user_dsc_ata), Some(collateral_vault)) = (user_dsc_ata, collateral_vault) else {
+        return err!(AssetErrors::InvalidCollateralAccount);
+    };
+    let collateral_deposit = collateral_deposit(price, asset.collateral_ratio, amount)
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    let cpi_accounts = DSC_Transfer {
+        from: user_dsc_ata.to_account_info(),
+        to: collateral_vault.to_account_info(),
+        authority: user,
+    };
+    let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
+    transfer(cpi_ctx, collateral_deposit)?;
+    asset.total_collateral = asset
+        .total_collateral
+        .checked_add(collateral_deposit)
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    asset.collateralized_supply = asset
+        .collateralized_supply
+        .checked_add(amount)
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    let health_price = match feed {
+        Some(feed) => feed.collateral_price(),
+        None => price,
+    };
+    asset
+        .refresh_health(health_price)
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    Ok(())
+}
+
+// enforces supply and per-wallet caps on a mint of `amount` and records it for the holder
+pub(crate) fn record_asset_mint(
+    asset: &AssetData,
+    supply: u64,
+    wallet_count: &mut WalletMintCount,
+    amount: u64,
+) -> Result<()> {
+    require!(!asset.supply_locked, AssetErrors::SupplyLocked);
+    require!(!asset.unhealthy, AssetErrors::AssetUnhealthy);
+    let next_supply = supply
+        .checked_add(amount)
+        .ok_or(AssetErrors::SupplyCapExceeded)?;
+    require!(
+        next_supply <= asset.max_supply.unwrap_or(u64::MAX),
+        AssetErrors::SupplyCapExceeded
+    );
+    let minted = wallet_count
+        .minted
+        .checked_add(amount)
+        .ok_or(AssetErrors::WalletCapExceeded)?;
+    require!(
+        minted <= asset.max_per_wallet.unwrap_or(u64::MAX),
+        AssetErrors::WalletCapExceeded
+    );
+    wallet_count.minted = minted;
+    Ok(())
+}
+
+// creates the asset mint at its PDA, adding the Token-2022 extensions the asset mode needs
+fn create_asset_mint<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+    payer: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    mint_seeds: &[&[&[u8]]],
+    extensions: &[ExtensionType],
+) -> Result<()> {
+    require!(
+        extensions.is_empty() || token_program.key() == anchor_spl::token_2022::ID,
+        AssetErrors::InvalidContext
+    );
+    let space = ExtensionType::try_calculate_account_len::<MintState>(extensions)?;
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            CreateAccount {
+                from: payer,
+                to: mint.clone(),
+            },
+            mint_seeds,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &token_program.key(),
+    )?;
+    for extension in extensions {
+        match extension {
+            ExtensionType::TransferHook => transfer_hook_initialize(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    TransferHookInitialize {
+                        token_program_id: token_program.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                ),
+                Some(mint.key()),
+                Some(asset_transfer_hook::ID),
+            )?,
+            ExtensionType::NonTransferable => non_transferable_mint_initialize(CpiContext::new(
+                token_program.to_account_info(),
+                NonTransferableMintInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: mint.clone(),
+                },
+            ))?,
+            // the mint PDA doubles as permanent delegate so the game can claw back
+            ExtensionType::PermanentDelegate => permanent_delegate_initialize(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    PermanentDelegateInitialize {
+                        token_program_id: token_program.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                ),
+                &mint.key(),
+            )?,
+            _ => return err!(AssetErrors::InvalidArguments),
+        }
+    }
+    initialize_mint2(
+        CpiContext::new(
+            token_program.to_account_info(),
+            InitializeMint2 { mint: mint.clone() },
+        ),
+        0,
+        &mint.key(),
+        None,
+    )
 }
 
 pub fn intialize_asset_handler(
     ctx: Context<InitializeAssetDataContext>,
     args: InitializeAssetDataArgs,
 ) -> Result<()> {
+    // marketplace policies trust `game`, so it has to be the game the asset is created under
+    require!(
+        args.game_id == ctx.accounts.game_account.key(),
+        AssetErrors::InvalidGameOrAssetAccount
+    );
     let asset = &mut ctx.accounts.asset_account;
     asset.game = args.game_id;
     require!(args.name.len() < 20, AssetErrors::InvalidArguments);
@@ -33,7 +206,11 @@ pub fn intialize_asset_handler(
     asset.uri = args.uri;
     let signer = &ctx.accounts.creator;
     require!(
-        signer.key() == ctx.accounts.game_account.owner,
+        ctx.accounts.game_account.has_role(
+            &signer.key(),
+            ctx.accounts.signer_role.as_deref(),
+            GameRole::CREATE_ASSET
+        ),
         AssetErrors::InvalidOperation
     );
     asset.price = args.price;
@@ -41,6 +218,41 @@ pub fn intialize_asset_handler(
     asset.trade = args.trade_option;
     asset.collateral_option = args.collateral_option;
     asset.collateral_ratio = args.collateral_ratio;
+    require!(args.royalty_bps <= MAX_BPS, AssetErrors::InvalidRoyalty);
+    asset.royalty_bps = args.royalty_bps;
+    asset.royalty_recipient = args.royalty_recipient;
+    asset.royalty_enforced = args.royalty_enforced;
+    asset.max_supply = args.max_supply;
+    asset.max_per_wallet = args.max_per_wallet;
+    asset.supply_locked = false;
+    asset.total_collateral = 0;
+    asset.collateralized_supply = 0;
+    asset.price_feed = None;
+    asset.unhealthy = false;
+    let mut extensions = Vec::new();
+    // the hook program can't be called from here as it depends on this crate, so creators
+    // follow up with its `initialize_extra_account_meta_list` for this mint. Until then every
+    // transfer of the asset fails, marketplace ones included
+    if args.royalty_enforced {
+        require!(args.trade_option, AssetErrors::InvalidArguments);
+        extensions.push(ExtensionType::TransferHook);
+    }
+    // non-tradable assets are soulbound at the token level, not just in our handlers
+    if !args.trade_option {
+        extensions.push(ExtensionType::NonTransferable);
+        extensions.push(ExtensionType::PermanentDelegate);
+    }
+    let game_key = ctx.accounts.game_account.key();
+    let asset_key = asset.key();
+    let mint_seeds: &[&[&[u8]]] = &[&[game_key.as_ref(), asset_key.as_ref(), &[ctx.bumps.mint]]];
+    create_asset_mint(
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        mint_seeds,
+        &extensions,
+    )?;
     msg!("Asset Data initialized along with mint account for the assets ");
     Ok(())
 }
@@ -56,23 +268,26 @@ pub struct InitializeAssetDataContext<'info> {
         space = 8 + AssetData::INIT_SPACE
     )]
     pub asset_account: Account<'info, AssetData>,
+    /// CHECK: created by the handler under the requested token program
     #[account(
-        init,
+        mut,
         seeds = [game_account.key().as_ref(),asset_account.key().as_ref()],
         bump,
-        payer = creator,
-        mint::decimals = 0,
-        mint::authority = mint,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: UncheckedAccount<'info>,
     #[account(
-        seeds = [creator.key().as_ref(),game_account.name.as_bytes()],
-        bump
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump
     )]
     pub game_account: Account<'info, GameState>,
+    #[account(
+        seeds = [b"game_role",game_account.key().as_ref(),creator.key().as_ref()],
+        bump = signer_role.bump
+    )]
+    pub signer_role: Option<Account<'info, GameRole>>,
     #[account(mut)]
     pub creator: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
 }
@@ -81,33 +296,39 @@ pub struct InitializeAssetDataContext<'info> {
 pub struct MintAssetArgs {
     pub amount: u64,
     pub asset_name: String,
-    pub game_name: String,
     pub holder: Pubkey,
 }
 pub fn mint_asset_handler(ctx: Context<MintAssetContext>, args: MintAssetArgs) -> Result<()> {
     let signer = &ctx.accounts.user;
     let game_account = &ctx.accounts.game_account;
-    let asset_account = &ctx.accounts.asset_account;
+    let asset_account = &mut ctx.accounts.asset_account;
     require!(
-        signer.key() == game_account.owner,
+        game_account.has_role(
+            &signer.key(),
+            ctx.accounts.signer_role.as_deref(),
+            GameRole::MINT
+        ),
         AssetErrors::InvalidOperation,
     );
     require!(
         game_account.key() == asset_account.game,
         AssetErrors::InvalidGameOrAssetAccount
     );
-    msg!("asset_authority step");
-    msg!("token_program ...{}", ctx.accounts.token_program.key());
+    record_asset_mint(
+        asset_account,
+        ctx.accounts.mint.supply,
+        &mut ctx.accounts.wallet_mint_count,
+        args.amount,
+    )?;
     let asset_authority = &mut ctx.accounts.destination_ata_authority;
     asset_authority.user = args.holder;
-    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_program = ctx.accounts.asset_token_program.to_account_info();
     let cpi_accounts = MintTo {
         authority: ctx.accounts.mint.to_account_info(),
         to: ctx.accounts.destination_ata.to_account_info(),
         mint: ctx.accounts.mint.to_account_info(),
     };
-    msg!("before minting step");
-    
+
     let asset_acc_key = asset_account.key();
     let game_acc_key = game_account.key();
     let seeds: &[&[&[u8]]] = &[&[
@@ -117,21 +338,16 @@ pub fn mint_asset_handler(ctx: Context<MintAssetContext>, args: MintAssetArgs) -
     ]];
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds);
     mint_to(cpi_ctx, args.amount)?;
-    msg!("minted tokens {args.amount}");
-    if asset_account.collateral_option == true {
-        let collateral_ratio = asset_account.collateral_ratio.checked_div(100).unwrap();
-        let price = asset_account.price;
-        let collateral_factor = collateral_ratio.checked_mul(args.amount).unwrap();
-        let collateral_deposit = collateral_factor.checked_mul(price).unwrap();
-        let cpi_accounts = DSC_Transfer {
-            from: ctx.accounts.user_dsc_token_ata.to_account_info(),
-            to: ctx.accounts.collateral_token_account.to_account_info(),
-            authority: ctx.accounts.user.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        transfer(cpi_ctx, collateral_deposit)?;
-    }
+    msg!("minted tokens {}", args.amount);
+    deposit_asset_collateral(
+        asset_account,
+        args.amount,
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.user_dsc_token_ata.as_ref(),
+        ctx.accounts.collateral_vault.as_ref(),
+        ctx.accounts.price_feed.as_ref(),
+        signer.to_account_info(),
+    )?;
     Ok(())
 }
 
@@ -142,10 +358,12 @@ pub struct MintAssetContext<'info> {
         mut,
         seeds=[game_account.key().as_ref(),asset_account.key().as_ref()],
         bump,
-        mint::authority = mint
+        mint::authority = mint,
+        mint::token_program = asset_token_program,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
+        mut,
         seeds=[args.asset_name.as_bytes(),game_account.key().as_ref()],
         bump,
     )]
@@ -155,12 +373,20 @@ pub struct MintAssetContext<'info> {
         payer = user,
         associated_token::mint = mint,
         associated_token::authority = destination_ata_authority,
+        associated_token::token_program = asset_token_program,
     )]
-    pub destination_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub collateral_token_account: Account<'info, TokenAccount>,
+    pub destination_ata: InterfaceAccount<'info, TokenAccount>,
+    // only needed for assets minted against collateral
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", asset_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Option<Account<'info, DscTokenAccount>>,
     #[account(mut)]
-    pub user_dsc_token_ata: Account<'info, TokenAccount>,
+    pub user_dsc_token_ata: Option<Account<'info, DscTokenAccount>>,
+    #[account(address = asset_account.price_feed.unwrap_or_default())]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
     #[account(
         init_if_needed,
         payer = user,
@@ -170,14 +396,84 @@ pub struct MintAssetContext<'info> {
     )]
     pub destination_ata_authority: Account<'info, AssetAuthority>,
     #[account(
-        seeds = [user.key().as_ref(),args.game_name.as_bytes()],
-        bump
+        init_if_needed,
+        payer = user,
+        seeds = [b"wallet_mint", asset_account.key().as_ref(), args.holder.as_ref()],
+        bump,
+        space = 8 + WalletMintCount::INIT_SPACE
+    )]
+    pub wallet_mint_count: Account<'info, WalletMintCount>,
+    #[account(
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump
     )]
     pub game_account: Account<'info, GameState>,
+    #[account(
+        seeds = [b"game_role",game_account.key().as_ref(),user.key().as_ref()],
+        bump = signer_role.bump
+    )]
+    pub signer_role: Option<Account<'info, GameRole>>,
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct InitCollateralVaultArgs {
+    pub game_id: Pubkey,
+    pub asset_name: String,
+}
+
+pub fn init_collateral_vault_handler(
+    ctx: Context<InitCollateralVaultContext>,
+    _args: InitCollateralVaultArgs,
+) -> Result<()> {
+    require!(
+        ctx.accounts.asset_account.collateral_option,
+        AssetErrors::InvalidArguments
+    );
+    msg!(
+        "collateral vault for {} initialized",
+        ctx.accounts.asset_account.name
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: InitCollateralVaultArgs)]
+pub struct InitCollateralVaultContext<'info> {
+    #[account(
+        seeds = [args.asset_name.as_bytes(), args.game_id.as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(
+        init,
+        token::mint = dsc_mint,
+        token::authority = vault_authority,
+        seeds = [b"collateral_vault", asset_account.key().as_ref()],
+        bump,
+        payer = payer,
+    )]
+    pub collateral_vault: Account<'info, DscTokenAccount>,
+    /// CHECK: PDA, auth over all token vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        seeds = [b"mint"],
+        bump,
+        seeds::program = stable_coin::ID
+    )]
+    pub dsc_mint: Account<'info, DscMint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
@@ -1,12 +1,50 @@
 use crate::{
     errors::asset_errors::AssetErrors,
+    instructions::{
+        asset_initialize_instructions::{deposit_asset_collateral, record_asset_mint},
+        price_feed_instructions::health_price,
+    },
     state::{asset_state::*, game_state::*},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer as SplTransfer},
+    token::{transfer, Token, TokenAccount as DscTokenAccount, Transfer as DscTransfer},
+    token_2022::spl_token_2022::instruction::AuthorityType,
+    token_2022::spl_token_2022::onchain::invoke_transfer_checked,
+    token_interface::{
+        burn, mint_to, set_authority, Burn, Mint, MintTo, SetAuthority, TokenAccount,
+        TokenInterface,
+    },
 };
+use stable_coin::state::oracle_state::PriceFeed;
+
+// moves asset tokens under either token program, `hook_accounts` carries whatever a
+// royalty-enforced mint's transfer hook needs and is empty for plain mints
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_asset<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint.to_account_info(),
+        to,
+        authority,
+        hook_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct MintAuthorizedAssetArgs {
@@ -15,8 +53,6 @@ pub struct MintAuthorizedAssetArgs {
     pub amount: u64,
     pub name: String,
     pub holder: Pubkey,
-    pub game_owner: Pubkey,
-    pub game_name: String,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -34,7 +70,7 @@ pub fn mint_authorized_asset_handler(
 ) -> Result<()> {
     let signer = &ctx.accounts.user;
     let mint_authority = &ctx.accounts.mint_authority;
-    let asset_account = &ctx.accounts.asset_account;
+    let asset_account = &mut ctx.accounts.asset_account;
     let game = ctx.accounts.game_account.key();
     require!(
         signer.key() == mint_authority.user,
@@ -44,6 +80,22 @@ pub fn mint_authorized_asset_handler(
         game == asset_account.game,
         AssetErrors::InvalidGameOrAssetAccount
     );
+    record_asset_mint(
+        asset_account,
+        ctx.accounts.mint.supply,
+        &mut ctx.accounts.wallet_mint_count,
+        args.amount,
+    )?;
+    deposit_asset_collateral(
+        asset_account,
+        args.amount,
+        ctx.accounts.dsc_token_program.to_account_info(),
+        ctx.accounts.user_dsc_token_ata.as_ref(),
+        ctx.accounts.collateral_vault.as_ref(),
+        ctx.accounts.price_feed.as_ref(),
+        signer.to_account_info(),
+    )?;
+    ctx.accounts.holder_ata_authority.user = args.holder;
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_accounts = MintTo {
         authority: ctx.accounts.mint.to_account_info(),
@@ -55,7 +107,7 @@ pub fn mint_authorized_asset_handler(
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds);
 
     mint_to(cpi_ctx, args.amount)?;
-    msg!("minted tokens {args.amount}");
+    msg!("minted tokens {}", args.amount);
     Ok(())
 }
 
@@ -65,20 +117,52 @@ pub struct MintAuthorizedAssetContext<'info> {
     #[account(
         mut,
         seeds=[game_account.key().as_ref(),asset_account.key().as_ref()],
-        bump
+        bump,
+        mint::token_program = token_program,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
+        mut,
         seeds=[args.name.as_bytes(),game_account.key().as_ref()],
         bump
     )]
     pub asset_account: Account<'info, AssetData>,
-    #[account(mut)]
-    pub token_ata: Account<'info, TokenAccount>,
     #[account(
-        seeds = [args.game_owner.as_ref(),args.game_name.as_bytes()],
+        mut,
+        seeds = [b"collateral_vault", asset_account.key().as_ref()],
         bump
     )]
+    pub collateral_vault: Option<Account<'info, DscTokenAccount>>,
+    #[account(mut)]
+    pub user_dsc_token_ata: Option<Account<'info, DscTokenAccount>>,
+    #[account(address = asset_account.price_feed.unwrap_or_default())]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+    #[account(
+        mut,
+        token::authority = holder_ata_authority,
+        token::token_program = token_program,
+    )]
+    pub token_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [args.holder.as_ref(), mint.key().as_ref()],
+        bump,
+        space = 8 + AssetAuthority::INIT_SPACE
+    )]
+    pub holder_ata_authority: Account<'info, AssetAuthority>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"wallet_mint", asset_account.key().as_ref(), args.holder.as_ref()],
+        bump,
+        space = 8 + WalletMintCount::INIT_SPACE
+    )]
+    pub wallet_mint_count: Account<'info, WalletMintCount>,
+    #[account(
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
     pub game_account: Account<'info, GameState>,
     #[account(
         seeds = [asset_account.key().as_ref(),game_account.key().as_ref(),user.key().as_ref()],
@@ -87,11 +171,13 @@ pub struct MintAuthorizedAssetContext<'info> {
     pub mint_authority: Account<'info, MintAuthority>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub dsc_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn transfer_assets_handler(
-    ctx: Context<TransferAssetContext>,
+pub fn transfer_assets_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TransferAssetContext<'info>>,
     args: TransferAssetArgs,
 ) -> Result<()> {
     let from_acc = &ctx.accounts.from_ata;
@@ -100,30 +186,39 @@ pub fn transfer_assets_handler(
     let token_program = &ctx.accounts.token_program;
     let asset_acc = &ctx.accounts.asset_account;
     let signer = &ctx.accounts.user;
-    require!(asset_acc.trade == true, AssetErrors::InvalidTransfer);
+    require!(asset_acc.trade, AssetErrors::InvalidTransfer);
+    // a direct transfer would pass the hook without paying the royalty
+    require!(!asset_acc.royalty_enforced, AssetErrors::RoyaltyEnforced);
     require!(
         from_acc_authority.user == signer.key(),
         AssetErrors::InvalidOperation
     );
-    let cpi_accounts = SplTransfer {
-        from: from_acc.to_account_info().clone(),
-        to: to_acc.to_account_info().clone(),
-        authority: from_acc_authority.to_account_info().clone(),
-    };
-
-    let cpi_program = token_program.to_account_info();
-    let from = from_acc.key();
-    let seeds: &[&[&[u8]]] = &[&[from.as_ref(), &[ctx.bumps.from_ata_authority]]];
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds);
-    transfer(cpi_ctx, args.amount)?;
+    let user = signer.key();
+    let seeds: &[&[&[u8]]] = &[&[
+        args.mint.as_ref(),
+        user.as_ref(),
+        &[ctx.bumps.from_ata_authority],
+    ]];
+    transfer_asset(
+        token_program.to_account_info(),
+        from_acc.to_account_info(),
+        &ctx.accounts.mint,
+        to_acc.to_account_info(),
+        from_acc_authority.to_account_info(),
+        ctx.remaining_accounts,
+        seeds,
+        args.amount,
+    )?;
     Ok(())
 }
 
 #[derive(Accounts)]
 #[instruction(args: TransferAssetArgs)]
 pub struct TransferAssetContext<'info> {
-    #[account(mut)]
-    pub from_ata: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub from_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = args.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         seeds = [args.mint.as_ref(),user.key().as_ref()],
         bump
@@ -134,11 +229,11 @@ pub struct TransferAssetContext<'info> {
         bump
     )]
     pub asset_account: Account<'info, AssetData>,
-    #[account(mut)]
-    pub to_ata: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub to_ata: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
@@ -163,9 +258,10 @@ pub struct InitAssetATAContext<'info> {
     #[account(
         mut,
         seeds = [_args.game_id.as_ref(),asset_account.key().as_ref()],
-        bump
+        bump,
+        mint::token_program = token_program,
     )]
-    pub mint_account: Account<'info, Mint>,
+    pub mint_account: InterfaceAccount<'info, Mint>,
     #[account(
         seeds = [_args.asset_name.as_bytes(),_args.game_id.as_ref()],
         bump
@@ -176,8 +272,9 @@ pub struct InitAssetATAContext<'info> {
         payer = user,
         associated_token::mint = mint_account,
         associated_token::authority = to_ata_authority,
+        associated_token::token_program = token_program,
     )]
-    pub to_ata: Account<'info, TokenAccount>,
+    pub to_ata: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init_if_needed,
         payer = user,
@@ -188,7 +285,529 @@ pub struct InitAssetATAContext<'info> {
     pub to_ata_authority: Account<'info, AssetAuthority>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct BurnAssetArgs {
+    pub asset_name: String,
+    pub asset_game_id: Pubkey,
+    pub amount: u64,
+}
+
+// hands a burner the burned tokens' share of the asset's collateral vault
+fn redeem_asset_collateral(ctx: &mut Context<BurnAssetContext>, amount: u64) -> Result<()> {
+    let asset_account = &mut ctx.accounts.asset_account;
+    if !asset_account.collateral_option || asset_account.collateralized_supply == 0 {
+        return Ok(());
+    }
+    let (Some(collateral_vault), Some(holder_dsc_ata)) = (
+        ctx.accounts.collateral_vault.as_ref(),
+        ctx.accounts.holder_dsc_ata.as_ref(),
+    ) else {
+        return err!(AssetErrors::InvalidCollateralAccount);
+    };
+    require!(
+        amount <= asset_account.collateralized_supply,
+        AssetErrors::CollateralUnderflow
+    );
+    let release = (asset_account.total_collateral as u128)
+        .checked_mul(amount as u128)
+        .and_then(|share| share.checked_div(asset_account.collateralized_supply as u128))
+        .and_then(|release| u64::try_from(release).ok())
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    asset_account.total_collateral = asset_account
+        .total_collateral
+        .checked_sub(release)
+        .ok_or(AssetErrors::CollateralUnderflow)?;
+    asset_account.collateralized_supply -= amount;
+    let price = health_price(asset_account, ctx.accounts.price_feed.as_ref())?;
+    asset_account
+        .refresh_health(price)
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    if release == 0 {
+        return Ok(());
+    }
+    let seeds: &[&[&[u8]]] = &[&[b"vault_authority", &[ctx.bumps.vault_authority]]];
+    let cpi_accounts = DscTransfer {
+        from: collateral_vault.to_account_info(),
+        to: holder_dsc_ata.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        seeds,
+    );
+    transfer(cpi_ctx, release)
+}
+
+pub fn burn_asset_handler(mut ctx: Context<BurnAssetContext>, args: BurnAssetArgs) -> Result<()> {
+    redeem_asset_collateral(&mut ctx, args.amount)?;
+    let holder = ctx.accounts.holder.key();
+    let mint = ctx.accounts.mint.key();
+    require!(
+        ctx.accounts.holder_ata_authority.user == holder,
+        AssetErrors::InvalidOperation
+    );
+    let seeds: &[&[&[u8]]] = &[&[
+        holder.as_ref(),
+        mint.as_ref(),
+        &[ctx.bumps.holder_ata_authority],
+    ]];
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.holder_ata.to_account_info(),
+        authority: ctx.accounts.holder_ata_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.asset_token_program.to_account_info(),
+        cpi_accounts,
+        seeds,
+    );
+    burn(cpi_ctx, args.amount)?;
+    msg!(
+        "burned {} of {}",
+        args.amount,
+        ctx.accounts.asset_account.name
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: BurnAssetArgs)]
+pub struct BurnAssetContext<'info> {
+    #[account(
+        mut,
+        seeds = [args.asset_game_id.as_ref(), asset_account.key().as_ref()],
+        bump,
+        mint::token_program = asset_token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [args.asset_name.as_bytes(), args.asset_game_id.as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = holder_ata_authority,
+    )]
+    pub holder_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [holder.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub holder_ata_authority: Account<'info, AssetAuthority>,
+    // only needed when the asset carries collateral
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", asset_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Option<Account<'info, DscTokenAccount>>,
+    #[account(mut)]
+    pub holder_dsc_ata: Option<Account<'info, DscTokenAccount>>,
+    #[account(address = asset_account.price_feed.unwrap_or_default())]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+    /// CHECK: PDA, auth over all token vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    pub holder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct ClawbackAssetArgs {
+    pub asset_name: String,
+    pub amount: u64,
+}
+
+// burns soulbound tokens out of any holder account using the mint's permanent delegate
+pub fn clawback_asset_handler(
+    ctx: Context<ClawbackAssetContext>,
+    args: ClawbackAssetArgs,
+) -> Result<()> {
+    let game = ctx.accounts.game_account.key();
+    require!(
+        ctx.accounts.game_account.owner == ctx.accounts.owner.key(),
+        AssetErrors::InvalidOperation
+    );
+    let asset_account = &mut ctx.accounts.asset_account;
+    require!(!asset_account.trade, AssetErrors::NotSoulbound);
+    // the backing stays in the vault and is shared by the remaining holders
+    if asset_account.collateral_option {
+        asset_account.collateralized_supply = asset_account
+            .collateralized_supply
+            .checked_sub(args.amount)
+            .ok_or(AssetErrors::CollateralUnderflow)?;
+    }
+    let asset = asset_account.key();
+    let seeds: &[&[&[u8]]] = &[&[game.as_ref(), asset.as_ref(), &[ctx.bumps.mint]]];
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.holder_ata.to_account_info(),
+        authority: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        seeds,
+    );
+    burn(cpi_ctx, args.amount)?;
+    msg!(
+        "clawed back {} of {}",
+        args.amount,
+        ctx.accounts.asset_account.name
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: ClawbackAssetArgs)]
+pub struct ClawbackAssetContext<'info> {
+    #[account(
+        mut,
+        seeds = [game_account.key().as_ref(), asset_account.key().as_ref()],
+        bump,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [args.asset_name.as_bytes(), game_account.key().as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(mut, token::mint = mint)]
+    pub holder_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [game_account.creator.as_ref(), game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
+    pub game_account: Account<'info, GameState>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct LockAssetSupplyArgs {
+    pub asset_name: String,
+}
+
+// drops the mint authority for good, the current supply becomes final
+pub fn lock_asset_supply_handler(
+    ctx: Context<LockAssetSupplyContext>,
+    _args: LockAssetSupplyArgs,
+) -> Result<()> {
+    let game = ctx.accounts.game_account.key();
+    require!(
+        ctx.accounts.game_account.owner == ctx.accounts.owner.key(),
+        AssetErrors::InvalidOperation
+    );
+    let asset_account = &mut ctx.accounts.asset_account;
+    require!(!asset_account.supply_locked, AssetErrors::SupplyLocked);
+    asset_account.supply_locked = true;
+    let asset = asset_account.key();
+    let seeds: &[&[&[u8]]] = &[&[game.as_ref(), asset.as_ref(), &[ctx.bumps.mint]]];
+    let cpi_accounts = SetAuthority {
+        current_authority: ctx.accounts.mint.to_account_info(),
+        account_or_mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        seeds,
+    );
+    set_authority(cpi_ctx, AuthorityType::MintTokens, None)?;
+    msg!(
+        "supply of {} locked at {}",
+        asset_account.name,
+        ctx.accounts.mint.supply
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_args: LockAssetSupplyArgs)]
+pub struct LockAssetSupplyContext<'info> {
+    #[account(
+        mut,
+        seeds = [game_account.key().as_ref(), asset_account.key().as_ref()],
+        bump,
+        mint::authority = mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [_args.asset_name.as_bytes(), game_account.key().as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(
+        seeds = [game_account.creator.as_ref(), game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
+    pub game_account: Account<'info, GameState>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct CollateralStatusArgs {
+    pub asset_name: String,
+    pub asset_game_id: Pubkey,
+}
+
+// read-only, the status comes back as return data
+pub fn get_collateral_status_handler(
+    ctx: Context<CollateralStatusContext>,
+    _args: CollateralStatusArgs,
+) -> Result<CollateralStatus> {
+    let asset_account = &ctx.accounts.asset_account;
+    let collateral_per_token = asset_account
+        .total_collateral
+        .checked_div(asset_account.collateralized_supply)
+        .unwrap_or(0);
+    Ok(CollateralStatus {
+        total_collateral: asset_account.total_collateral,
+        collateralized_supply: asset_account.collateralized_supply,
+        collateral_per_token,
+        vault_balance: ctx
+            .accounts
+            .collateral_vault
+            .as_ref()
+            .map_or(0, |vault| vault.amount),
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(_args: CollateralStatusArgs)]
+pub struct CollateralStatusContext<'info> {
+    #[account(
+        seeds = [_args.asset_name.as_bytes(), _args.asset_game_id.as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(
+        seeds = [b"collateral_vault", asset_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Option<Account<'info, DscTokenAccount>>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct RefreshAssetHealthArgs {
+    pub asset_name: String,
+    pub asset_game_id: Pubkey,
+}
+
+// permissionless, lifts the minting pause once the backing recovers or sets it after a
+// price drop without waiting for a liquidation
+pub fn refresh_asset_health_handler(
+    ctx: Context<RefreshAssetHealthContext>,
+    _args: RefreshAssetHealthArgs,
+) -> Result<()> {
+    let asset_account = &mut ctx.accounts.asset_account;
+    require!(
+        asset_account.collateral_option,
+        AssetErrors::InvalidCollateralAccount
+    );
+    let price = health_price(asset_account, ctx.accounts.price_feed.as_ref())?;
+    asset_account
+        .refresh_health(price)
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    msg!(
+        "{} unhealthy: {}",
+        asset_account.name,
+        asset_account.unhealthy
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_args: RefreshAssetHealthArgs)]
+pub struct RefreshAssetHealthContext<'info> {
+    #[account(
+        mut,
+        seeds = [_args.asset_name.as_bytes(), _args.asset_game_id.as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(address = asset_account.price_feed.unwrap_or_default())]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct LiquidateAssetArgs {
+    pub asset_name: String,
+    pub asset_game_id: Pubkey,
+    // DSC base units paid into the vault, only the shortfall is taken
+    pub repay_amount: u64,
+    // asset tokens burned from the liquidator's holding
+    pub burn_amount: u64,
+}
+
+// open to anyone while the vault is under-backed. Repaying tops the vault up by at most its
+// shortfall, burning retires backed supply for DSC out of the vault at the oracle price plus
+// `LIQUIDATION_BONUS_BPS`. The repayment lands first so it can fund the payout
+pub fn liquidate_asset_collateral_handler(
+    ctx: Context<LiquidateAssetContext>,
+    args: LiquidateAssetArgs,
+) -> Result<()> {
+    require!(
+        args.repay_amount > 0 || args.burn_amount > 0,
+        AssetErrors::InvalidArguments
+    );
+    let asset_account = &mut ctx.accounts.asset_account;
+    require!(
+        asset_account.collateral_option,
+        AssetErrors::InvalidCollateralAccount
+    );
+    let price = health_price(asset_account, ctx.accounts.price_feed.as_ref())?;
+    require!(
+        asset_account
+            .is_under_collateralized(price)
+            .ok_or(AssetErrors::CollateralOverflow)?,
+        AssetErrors::AssetHealthy
+    );
+
+    let repaid = args.repay_amount.min(
+        asset_account
+            .collateral_shortfall(price)
+            .ok_or(AssetErrors::CollateralOverflow)?,
+    );
+    if repaid > 0 {
+        let cpi_accounts = DscTransfer {
+            from: ctx.accounts.liquidator_dsc_ata.to_account_info(),
+            to: ctx.accounts.collateral_vault.to_account_info(),
+            authority: ctx.accounts.liquidator.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, repaid)?;
+        asset_account.total_collateral = asset_account
+            .total_collateral
+            .checked_add(repaid)
+            .ok_or(AssetErrors::CollateralOverflow)?;
+    }
+
+    if args.burn_amount > 0 {
+        let (Some(liquidator_ata), Some(liquidator_ata_authority), Some(authority_bump)) = (
+            ctx.accounts.liquidator_ata.as_ref(),
+            ctx.accounts.liquidator_ata_authority.as_ref(),
+            ctx.bumps.liquidator_ata_authority,
+        ) else {
+            return err!(AssetErrors::InvalidContext);
+        };
+        require!(
+            args.burn_amount <= asset_account.collateralized_supply,
+            AssetErrors::CollateralUnderflow
+        );
+        let liquidator = ctx.accounts.liquidator.key();
+        let mint = ctx.accounts.mint.key();
+        let seeds: &[&[&[u8]]] = &[&[liquidator.as_ref(), mint.as_ref(), &[authority_bump]]];
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: liquidator_ata.to_account_info(),
+            authority: liquidator_ata_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.asset_token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        burn(cpi_ctx, args.burn_amount)?;
+
+        let payout = asset_account
+            .liquidation_payout(price, args.burn_amount)
+            .ok_or(AssetErrors::CollateralOverflow)?;
+        asset_account.total_collateral -= payout;
+        asset_account.collateralized_supply -= args.burn_amount;
+        let seeds: &[&[&[u8]]] = &[&[b"vault_authority", &[ctx.bumps.vault_authority]]];
+        let cpi_accounts = DscTransfer {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            to: ctx.accounts.liquidator_dsc_ata.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        transfer(cpi_ctx, payout)?;
+    }
+
+    asset_account
+        .refresh_health(price)
+        .ok_or(AssetErrors::CollateralOverflow)?;
+    msg!(
+        "{} liquidated, {} DSC backing {} tokens, unhealthy: {}",
+        asset_account.name,
+        asset_account.total_collateral,
+        asset_account.collateralized_supply,
+        asset_account.unhealthy
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: LiquidateAssetArgs)]
+pub struct LiquidateAssetContext<'info> {
+    #[account(
+        mut,
+        seeds = [args.asset_game_id.as_ref(), asset_account.key().as_ref()],
+        bump,
+        mint::token_program = asset_token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [args.asset_name.as_bytes(), args.asset_game_id.as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    // only needed to burn
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = liquidator_ata_authority,
+    )]
+    pub liquidator_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [liquidator.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub liquidator_ata_authority: Option<Account<'info, AssetAuthority>>,
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", asset_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, DscTokenAccount>,
+    #[account(
+        mut,
+        token::mint = collateral_vault.mint,
+        token::authority = liquidator,
+    )]
+    pub liquidator_dsc_ata: Account<'info, DscTokenAccount>,
+    #[account(address = asset_account.price_feed.unwrap_or_default())]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+    /// CHECK: PDA, auth over all token vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    pub liquidator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, TokenInterface>,
+}
@@ -1,13 +1,167 @@
 use crate::{
     errors::marketplace_errors::*,
+    events::marketplace_events::*,
+    instructions::{
+        asset_management_instructions::transfer_asset, price_feed_instructions::fresh_price_feed,
+    },
+    program::IndieGames,
     state::{asset_state::*, marketplace_state::*},
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer, Token, TokenAccount, Transfer as SPLTransfer};
+use anchor_lang::system_program::{transfer as transfer_lamports, Transfer as LamportTransfer};
+use anchor_spl::{
+    token::{transfer, Token, TokenAccount, Transfer as SPLTransfer},
+    token_interface::{
+        close_account as close_asset_account, CloseAccount as CloseAssetAccount, Mint as AssetMint,
+        TokenAccount as AssetTokenAccount, TokenInterface as AssetTokenInterface,
+    },
+};
+use stable_coin::state::oracle_state::PriceFeed;
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct MarketplaceConfigArgs {
+    pub fee_bps: u16,
+    pub fee_treasury: Pubkey,
+    pub allowed_payment_mints: Vec<Pubkey>,
+    pub min_price: u64,
+    pub max_price: u64,
+    pub allow_foreign_assets: bool,
+    pub oracle_floor_bps: u16,
+}
+
+pub(crate) fn apply_marketplace_config(
+    marketplace: &mut Marketplace,
+    args: MarketplaceConfigArgs,
+) -> Result<()> {
+    require!(args.fee_bps <= MAX_BPS, MarketplaceError::InvalidFee);
+    require!(
+        args.oracle_floor_bps <= MAX_BPS
+            && args.allowed_payment_mints.len() <= MAX_PAYMENT_MINTS
+            && (args.max_price == 0 || args.max_price >= args.min_price),
+        MarketplaceError::InvalidMarketplaceConfig
+    );
+    marketplace.fee_bps = args.fee_bps;
+    marketplace.fee_treasury = args.fee_treasury;
+    marketplace.allowed_payment_mints = args.allowed_payment_mints;
+    marketplace.min_price = args.min_price;
+    marketplace.max_price = args.max_price;
+    marketplace.allow_foreign_assets = args.allow_foreign_assets;
+    marketplace.oracle_floor_bps = args.oracle_floor_bps;
+    Ok(())
+}
+
+// a venue with `oracle_floor_bps` set rejects DSC prices under that share of the feed price
+pub(crate) fn check_oracle_floor(
+    venue: &Marketplace,
+    asset_account: &Account<AssetData>,
+    price_feed: Option<&Account<PriceFeed>>,
+    payment_mint: &Pubkey,
+    floor_price: u64,
+) -> Result<()> {
+    if venue.oracle_floor_bps == 0 {
+        return Ok(());
+    }
+    if let Some(feed) = fresh_price_feed(asset_account, price_feed)? {
+        // only DSC prices are comparable to the feed
+        if *payment_mint == DSC_MINT {
+            let oracle_floor = collateral_deposit(
+                feed.price.saturating_sub(feed.confidence),
+                venue.oracle_floor_bps as u64,
+                1,
+            )
+            .ok_or(MarketplaceError::ArithmeticError)?;
+            require!(
+                floor_price >= oracle_floor,
+                MarketplaceError::ListingPolicyViolation
+            );
+        }
+    }
+    Ok(())
+}
 
-pub fn initialize_marketplace_handler(ctx: Context<InitMarketplaceContext>) -> Result<()> {
+// only the program's upgrade authority can create the global venue and become its admin
+pub fn initialize_marketplace_handler(
+    ctx: Context<InitMarketplaceContext>,
+    args: MarketplaceConfigArgs,
+) -> Result<()> {
     let marketplace = &mut ctx.accounts.marketplace;
     marketplace.current_listing_id = 0;
+    marketplace.admin = ctx.accounts.initializer.key();
+    marketplace.game = None;
+    apply_marketplace_config(marketplace, args)
+}
+
+pub fn update_marketplace_config_handler(
+    ctx: Context<UpdateMarketplaceConfigContext>,
+    args: MarketplaceConfigArgs,
+) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    require!(
+        marketplace.admin == ctx.accounts.admin.key(),
+        MarketplaceError::NotAuthorized
+    );
+    apply_marketplace_config(marketplace, args)
+}
+
+// moves a settled payment out of `from` to the seller, the fee treasury and the royalty recipient,
+// whose account is only required when the sale actually owes a royalty
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pay_sale_proceeds<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    seller_ata: AccountInfo<'info>,
+    fee_treasury_ata: AccountInfo<'info>,
+    royalty_ata: Option<AccountInfo<'info>>,
+    proceeds: &SaleProceeds,
+) -> Result<()> {
+    for (to, amount) in [
+        (Some(seller_ata), proceeds.seller_proceeds),
+        (Some(fee_treasury_ata), proceeds.protocol_fee),
+        (royalty_ata, proceeds.royalty),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let to = to.ok_or(MarketplaceError::InvalidPaymentAccount)?;
+        let cpi_accounts = SPLTransfer {
+            from: from.clone(),
+            to,
+            authority: authority.clone(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, amount)?;
+    }
+    Ok(())
+}
+
+// native SOL counterpart of `pay_sale_proceeds`, paid straight out of the buyer's wallet
+pub(crate) fn pay_sale_proceeds_in_lamports<'info>(
+    system_program: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    seller: AccountInfo<'info>,
+    fee_treasury: AccountInfo<'info>,
+    royalty_recipient: Option<AccountInfo<'info>>,
+    proceeds: &SaleProceeds,
+) -> Result<()> {
+    for (to, amount) in [
+        (Some(seller), proceeds.seller_proceeds),
+        (Some(fee_treasury), proceeds.protocol_fee),
+        (royalty_recipient, proceeds.royalty),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let to = to.ok_or(MarketplaceError::InvalidPaymentAccount)?;
+        let cpi_accounts = LamportTransfer {
+            from: payer.clone(),
+            to,
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        transfer_lamports(cpi_ctx, amount)?;
+    }
     Ok(())
 }
 
@@ -21,11 +175,30 @@ pub struct InitMarketplaceContext<'info> {
         payer = initializer
     )]
     pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ MarketplaceError::NotAuthorized
+    )]
+    pub program: Program<'info, IndieGames>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(initializer.key()) @ MarketplaceError::NotAuthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
     #[account(mut)]
     pub initializer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateMarketplaceConfigContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    pub admin: Signer<'info>,
+}
+
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct ListAssetArgs {
     pub asset_name: String,
@@ -34,34 +207,107 @@ pub struct ListAssetArgs {
     pub asset_game_id: Pubkey,
     pub dsc_credit_ata: Pubkey,
     pub asset_mint: Pubkey,
+    pub dutch_auction: Option<DutchAuction>,
+    pub expires_at: Option<i64>,
+    pub payment_mint: Pubkey,
 }
 
-pub fn list_for_sale_handler(ctx: Context<ListForSaleContext>, args: ListAssetArgs) -> Result<()> {
+pub fn list_for_sale_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ListForSaleContext<'info>>,
+    args: ListAssetArgs,
+) -> Result<()> {
     let asset_account = &ctx.accounts.asset_account;
-    require!(asset_account.trade == true, MarketplaceError::CantListAsset);
+    require!(asset_account.trade, MarketplaceError::CantListAsset);
+    let sale_price = match &args.dutch_auction {
+        Some(dutch_auction) => {
+            require!(dutch_auction.is_valid(), MarketplaceError::InvalidPrice);
+            dutch_auction.start_price
+        }
+        None => args.sale_price,
+    };
+    require!(sale_price > 0, MarketplaceError::InvalidPrice);
+    let venue = match &ctx.accounts.game_marketplace {
+        Some(game_marketplace) => game_marketplace,
+        None => &ctx.accounts.marketplace,
+    };
+    let floor_price = args
+        .dutch_auction
+        .map_or(sale_price, |dutch_auction| dutch_auction.floor_price);
+    require!(
+        venue.accepts_asset(&asset_account.game)
+            && venue.accepts_price(sale_price)
+            && venue.accepts_price(floor_price),
+        MarketplaceError::ListingPolicyViolation
+    );
+    require!(
+        venue.accepts_payment_mint(&args.payment_mint),
+        MarketplaceError::PaymentMintNotAllowed
+    );
+    check_oracle_floor(
+        venue,
+        asset_account,
+        ctx.accounts.price_feed.as_ref(),
+        &args.payment_mint,
+        floor_price,
+    )?;
+    let venue_key = venue.key();
+    // SOL proceeds go to the seller's wallet, token proceeds to a matching token account
+    let dsc_credit_ata = if args.payment_mint == NATIVE_SOL_PAYMENT {
+        ctx.accounts.seller.key()
+    } else {
+        ctx.accounts
+            .seller_dsc_ata
+            .as_ref()
+            .ok_or(MarketplaceError::InvalidPaymentAccount)?
+            .key()
+    };
+    if let Some(expires_at) = args.expires_at {
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            MarketplaceError::ListingExpired
+        );
+    }
+    let seller = ctx.accounts.seller.key();
+    let seller_ata = &ctx.accounts.seller_asset_ata;
+    let seller_ata_authority = &ctx.accounts.seller_asset_ata_authority;
+    require!(
+        seller_ata_authority.user == seller && seller_ata.owner == seller_ata_authority.key(),
+        MarketplaceError::NotAuthorized
+    );
     let market = &mut ctx.accounts.marketplace;
     let sale_acc = &mut ctx.accounts.sale_acc;
     sale_acc.listing_id = market.current_listing_id;
-    sale_acc.price = args.sale_price;
+    sale_acc.price = sale_price;
     sale_acc.sale_amount = args.sale_amount;
-    sale_acc.dsc_credit_ata = args.dsc_credit_ata;
+    sale_acc.dsc_credit_ata = dsc_credit_ata;
     sale_acc.sale_state = 0;
-    market.current_listing_id = market.current_listing_id.checked_add(1).unwrap();
-    let seller_ata = &ctx.accounts.seller_asset_ata;
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let ata_key = seller_ata.key();
-    require!(
-        ata_key == ctx.accounts.seller.key(),
-        MarketplaceError::NotAuthorized
-    );
-    let cpi_accounts = SPLTransfer {
-        from: seller_ata.to_account_info(),
-        to: ctx.accounts.market_asset_ata.to_account_info(),
-        authority: ctx.accounts.seller_asset_ata_authority.to_account_info(),
-    };
-    let seeds: &[&[&[u8]]] = &[&[ata_key.as_ref()]];
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds);
-    transfer(cpi_ctx, args.sale_amount)?;
+    sale_acc.seller = seller;
+    sale_acc.escrow_ata = ctx.accounts.market_asset_ata.key();
+    sale_acc.asset_account = asset_account.key();
+    sale_acc.dutch_auction = args.dutch_auction;
+    sale_acc.seller_asset_ata = seller_ata.key();
+    sale_acc.expires_at = args.expires_at;
+    sale_acc.marketplace = venue_key;
+    sale_acc.payment_mint = args.payment_mint;
+    market.current_listing_id = market
+        .current_listing_id
+        .checked_add(1)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    let seeds: &[&[&[u8]]] = &[&[
+        seller.as_ref(),
+        args.asset_mint.as_ref(),
+        &[ctx.bumps.seller_asset_ata_authority],
+    ]];
+    transfer_asset(
+        ctx.accounts.token_program.to_account_info(),
+        seller_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.market_asset_ata.to_account_info(),
+        seller_ata_authority.to_account_info(),
+        ctx.remaining_accounts,
+        seeds,
+        args.sale_amount,
+    )?;
 
     Ok(())
 }
@@ -69,17 +315,33 @@ pub fn list_for_sale_handler(ctx: Context<ListForSaleContext>, args: ListAssetAr
 #[derive(Accounts)]
 #[instruction(args: ListAssetArgs)]
 pub struct ListForSaleContext<'info> {
-    #[account(mut)]
-    pub market_asset_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = asset_mint,
+        token::authority = market_asset_ata_authority,
+        token::token_program = token_program,
+    )]
+    pub market_asset_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    #[account(address = args.asset_mint)]
+    pub asset_mint: InterfaceAccount<'info, AssetMint>,
+    /// CHECK: PDA signer of the escrow account, holds no data
+    #[account(
+        seeds = [market_asset_ata.key().as_ref()],
+        bump
+    )]
+    pub market_asset_ata_authority: AccountInfo<'info>,
     #[account(
         seeds=[args.asset_name.as_bytes(),args.asset_game_id.key().as_ref()],
         bump,
     )]
     pub asset_account: Account<'info, AssetData>,
-    #[account(mut)]
-    pub seller_asset_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub seller_dsc_ata: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = asset_mint)]
+    pub seller_asset_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    #[account(
+        address = args.dsc_credit_ata @ MarketplaceError::InvalidPaymentAccount,
+        token::mint = args.payment_mint,
+    )]
+    pub seller_dsc_ata: Option<Account<'info, TokenAccount>>,
     #[account(
         seeds = [seller.key().as_ref(),args.asset_mint.as_ref()],
         bump
@@ -89,10 +351,18 @@ pub struct ListForSaleContext<'info> {
     pub seller: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"market_place"],
+        seeds = [b"marketplace"],
         bump,
     )]
     pub marketplace: Account<'info, Marketplace>,
+    // lists into a game's own book instead of the global one
+    #[account(
+        seeds = [b"marketplace", game_marketplace.game.unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub game_marketplace: Option<Account<'info, Marketplace>>,
+    #[account(address = asset_account.price_feed.unwrap_or_default())]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
     #[account(
         init,
         seeds = [marketplace.current_listing_id.to_string().as_bytes()],
@@ -101,49 +371,184 @@ pub struct ListForSaleContext<'info> {
         space = 8 + Sale::INIT_SPACE
     )]
     pub sale_acc: Account<'info, Sale>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, AssetTokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn buy_from_sale_handler(ctx: Context<BuyFromSaleContext>) -> Result<()> {
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct BuyFromSaleArgs {
+    pub quantity: u64,
+    // highest per-unit price the buyer accepts
+    pub max_price: u64,
+}
+
+pub fn buy_from_sale_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BuyFromSaleContext<'info>>,
+    args: BuyFromSaleArgs,
+) -> Result<()> {
     let sale_acc = &mut ctx.accounts.sale_acc;
     require!(sale_acc.sale_state == 0, MarketplaceError::SaleNotFound);
-    sale_acc.sale_state = 1;
-    let dsc_cpi_accounts = SPLTransfer {
-        from: ctx.accounts.buyer_dsc_ata.to_account_info(),
-        to: ctx.accounts.seller_dsc_ata.to_account_info(),
-        authority: ctx.accounts.user.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let dsc_cpi_ctx = CpiContext::new(cpi_program, dsc_cpi_accounts);
-    transfer(dsc_cpi_ctx, sale_acc.price)?;
-
-    let asset_cpi_accounts = SPLTransfer {
-        from: ctx.accounts.asset_holding_ata.to_account_info(),
-        to: ctx.accounts.buyer_asset_ata.to_account_info(),
-        authority: ctx.accounts.asset_holding_ata_authority.to_account_info(),
+    require!(
+        !sale_acc.is_expired(Clock::get()?.unix_timestamp),
+        MarketplaceError::ListingExpired
+    );
+    require!(args.quantity > 0, MarketplaceError::InvalidQuantity);
+    require!(
+        args.quantity <= sale_acc.sale_amount,
+        MarketplaceError::QuantityExceedsListing
+    );
+    let unit_price = match &sale_acc.dutch_auction {
+        Some(dutch_auction) => {
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                now >= dutch_auction.start_time,
+                MarketplaceError::AuctionNotActive
+            );
+            dutch_auction
+                .price_at(now)
+                .ok_or(MarketplaceError::ArithmeticError)?
+        }
+        None => sale_acc.price,
     };
+    require!(
+        unit_price <= args.max_price,
+        MarketplaceError::PriceAboveMax
+    );
+    let total_price = unit_price
+        .checked_mul(args.quantity)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    sale_acc.sale_amount = sale_acc
+        .sale_amount
+        .checked_sub(args.quantity)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    let proceeds = split_sale_proceeds(
+        total_price,
+        ctx.accounts.marketplace.fee_bps,
+        ctx.accounts.asset_account.royalty_bps,
+    )
+    .ok_or(MarketplaceError::ArithmeticError)?;
+    if sale_acc.is_paid_in_sol() {
+        let fee_treasury = ctx
+            .accounts
+            .fee_treasury
+            .as_ref()
+            .ok_or(MarketplaceError::InvalidPaymentAccount)?;
+        pay_sale_proceeds_in_lamports(
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.seller.to_account_info(),
+            fee_treasury.to_account_info(),
+            ctx.accounts
+                .royalty_recipient
+                .as_ref()
+                .map(|recipient| recipient.to_account_info()),
+            &proceeds,
+        )?;
+    } else {
+        let (Some(buyer_dsc_ata), Some(seller_dsc_ata), Some(fee_treasury_ata)) = (
+            ctx.accounts.buyer_dsc_ata.as_ref(),
+            ctx.accounts.seller_dsc_ata.as_ref(),
+            ctx.accounts.fee_treasury_ata.as_ref(),
+        ) else {
+            return err!(MarketplaceError::InvalidPaymentAccount);
+        };
+        pay_sale_proceeds(
+            ctx.accounts.token_program.to_account_info(),
+            buyer_dsc_ata.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            &[],
+            seller_dsc_ata.to_account_info(),
+            fee_treasury_ata.to_account_info(),
+            ctx.accounts
+                .royalty_ata
+                .as_ref()
+                .map(|royalty_ata| royalty_ata.to_account_info()),
+            &proceeds,
+        )?;
+    }
+
     let holding_ata = ctx.accounts.asset_holding_ata.key();
     let seeds: &[&[&[u8]]] = &[&[
         holding_ata.as_ref(),
         &[ctx.bumps.asset_holding_ata_authority],
     ]];
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let asset_cpi_ctx = CpiContext::new_with_signer(cpi_program, asset_cpi_accounts, seeds);
-    transfer(asset_cpi_ctx, sale_acc.sale_amount)?;
+    transfer_asset(
+        ctx.accounts.asset_token_program.to_account_info(),
+        ctx.accounts.asset_holding_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.buyer_asset_ata.to_account_info(),
+        ctx.accounts.asset_holding_ata_authority.to_account_info(),
+        ctx.remaining_accounts,
+        seeds,
+        args.quantity,
+    )?;
+
+    emit!(SaleSettled {
+        listing_id: sale_acc.listing_id,
+        buyer: ctx.accounts.user.key(),
+        seller: sale_acc.seller,
+        quantity: args.quantity,
+        total_price,
+        protocol_fee: proceeds.protocol_fee,
+        royalty: proceeds.royalty,
+        seller_proceeds: proceeds.seller_proceeds,
+    });
+    if sale_acc.sale_amount == 0 {
+        sale_acc.sale_state = 1;
+        close_sale_escrow(
+            ctx.accounts.asset_token_program.to_account_info(),
+            &mut ctx.accounts.asset_holding_ata,
+            ctx.accounts.asset_holding_ata_authority.to_account_info(),
+            ctx.bumps.asset_holding_ata_authority,
+            ctx.accounts.seller.to_account_info(),
+        )?;
+        sale_acc.close(ctx.accounts.seller.to_account_info())?;
+        msg!("listing {} sold out", sale_acc.listing_id);
+    }
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct BuyFromSaleContext<'info> {
-    #[account(mut)]
-    pub buyer_dsc_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub seller_dsc_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub buyer_asset_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub asset_holding_ata: Account<'info, TokenAccount>,
+    // token accounts settle listings priced in an SPL mint
+    #[account(mut, token::mint = sale_acc.payment_mint)]
+    pub buyer_dsc_ata: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = sale_acc.dsc_credit_ata @ MarketplaceError::InvalidPaymentAccount,
+        token::mint = sale_acc.payment_mint,
+    )]
+    pub seller_dsc_ata: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = sale_acc.payment_mint,
+        constraint = fee_treasury_ata.owner == marketplace.fee_treasury @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub fee_treasury_ata: Option<Account<'info, TokenAccount>>,
+    // royalty accounts may be left out when the asset charges no royalty
+    #[account(
+        mut,
+        token::mint = sale_acc.payment_mint,
+        constraint = royalty_ata.owner == asset_account.royalty_recipient @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub royalty_ata: Option<Account<'info, TokenAccount>>,
+    // wallets settle listings priced in native SOL
+    /// CHECK: only receives lamports
+    #[account(mut, address = marketplace.fee_treasury @ MarketplaceError::InvalidPaymentAccount)]
+    pub fee_treasury: Option<AccountInfo<'info>>,
+    /// CHECK: only receives lamports
+    #[account(mut, address = asset_account.royalty_recipient @ MarketplaceError::InvalidPaymentAccount)]
+    pub royalty_recipient: Option<AccountInfo<'info>>,
+    #[account(address = sale_acc.marketplace)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(address = sale_acc.asset_account)]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(mut, token::mint = asset_mint)]
+    pub buyer_asset_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    #[account(mut, token::mint = asset_mint)]
+    pub asset_holding_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    #[account(mint::token_program = asset_token_program)]
+    pub asset_mint: InterfaceAccount<'info, AssetMint>,
     /// CHECK: unsafe
     #[account(
         seeds = [asset_holding_ata.key().as_ref()],
@@ -151,11 +556,245 @@ pub struct BuyFromSaleContext<'info> {
     )]
     pub asset_holding_ata_authority: AccountInfo<'info>,
     #[account(
+        mut,
         seeds = [&sale_acc.listing_id.to_string().as_bytes()],
-        bump
+        bump,
+        constraint = sale_acc.escrow_ata == asset_holding_ata.key() @ MarketplaceError::SaleNotFound
     )]
     pub sale_acc: Account<'info, Sale>,
+    /// CHECK: receives SOL proceeds and the listing rent once it sells out
+    #[account(mut, address = sale_acc.seller @ MarketplaceError::NotAuthorized)]
+    pub seller: AccountInfo<'info>,
     #[account(mut)]
     pub user: Signer<'info>,
     pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// hands the unsold part of a listing back out of the market escrow
+#[allow(clippy::too_many_arguments)]
+fn return_sale_escrow<'info>(
+    token_program: AccountInfo<'info>,
+    market_asset_ata: AccountInfo<'info>,
+    asset_mint: &InterfaceAccount<'info, AssetMint>,
+    market_asset_ata_authority: AccountInfo<'info>,
+    escrow_bump: u8,
+    seller_asset_ata: AccountInfo<'info>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    let escrow_ata = market_asset_ata.key();
+    let seeds: &[&[&[u8]]] = &[&[escrow_ata.as_ref(), &[escrow_bump]]];
+    transfer_asset(
+        token_program,
+        market_asset_ata,
+        asset_mint,
+        seller_asset_ata,
+        market_asset_ata_authority,
+        hook_accounts,
+        seeds,
+        amount,
+    )
+}
+
+// closes the market escrow once it holds nothing, handing its rent back to the seller
+fn close_sale_escrow<'info>(
+    token_program: AccountInfo<'info>,
+    market_asset_ata: &mut InterfaceAccount<'info, AssetTokenAccount>,
+    market_asset_ata_authority: AccountInfo<'info>,
+    escrow_bump: u8,
+    seller: AccountInfo<'info>,
+) -> Result<()> {
+    market_asset_ata.reload()?;
+    if market_asset_ata.amount > 0 {
+        return Ok(());
+    }
+    let escrow_ata = market_asset_ata.key();
+    let seeds: &[&[&[u8]]] = &[&[escrow_ata.as_ref(), &[escrow_bump]]];
+    let cpi_accounts = CloseAssetAccount {
+        account: market_asset_ata.to_account_info(),
+        destination: seller,
+        authority: market_asset_ata_authority,
+    };
+    close_asset_account(CpiContext::new_with_signer(
+        token_program,
+        cpi_accounts,
+        seeds,
+    ))
+}
+
+pub fn cancel_listing_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelListingContext<'info>>,
+) -> Result<()> {
+    let sale_acc = &ctx.accounts.sale_acc;
+    require!(sale_acc.sale_state == 0, MarketplaceError::SaleNotFound);
+    require!(
+        sale_acc.seller == ctx.accounts.seller.key(),
+        MarketplaceError::NotAuthorized
+    );
+    return_sale_escrow(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.market_asset_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.market_asset_ata_authority.to_account_info(),
+        ctx.bumps.market_asset_ata_authority,
+        ctx.accounts.seller_asset_ata.to_account_info(),
+        ctx.remaining_accounts,
+        sale_acc.sale_amount,
+    )?;
+    close_sale_escrow(
+        ctx.accounts.token_program.to_account_info(),
+        &mut ctx.accounts.market_asset_ata,
+        ctx.accounts.market_asset_ata_authority.to_account_info(),
+        ctx.bumps.market_asset_ata_authority,
+        ctx.accounts.seller.to_account_info(),
+    )?;
+    msg!("listing {} cancelled", sale_acc.listing_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelListingContext<'info> {
+    #[account(
+        mut,
+        seeds = [&sale_acc.listing_id.to_string().as_bytes()],
+        bump,
+        close = seller,
+        constraint = sale_acc.escrow_ata == market_asset_ata.key() @ MarketplaceError::SaleNotFound
+    )]
+    pub sale_acc: Account<'info, Sale>,
+    #[account(mut, token::mint = asset_mint)]
+    pub market_asset_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub asset_mint: InterfaceAccount<'info, AssetMint>,
+    /// CHECK: PDA signer of the escrow account, holds no data
+    #[account(
+        seeds = [market_asset_ata.key().as_ref()],
+        bump
+    )]
+    pub market_asset_ata_authority: AccountInfo<'info>,
+    #[account(mut, token::mint = asset_mint)]
+    pub seller_asset_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub token_program: Interface<'info, AssetTokenInterface>,
+}
+
+pub fn expire_listing_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExpireListingContext<'info>>,
+) -> Result<()> {
+    let sale_acc = &ctx.accounts.sale_acc;
+    require!(sale_acc.sale_state == 0, MarketplaceError::SaleNotFound);
+    require!(
+        sale_acc.is_expired(Clock::get()?.unix_timestamp),
+        MarketplaceError::ListingNotExpired
+    );
+    return_sale_escrow(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.market_asset_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.market_asset_ata_authority.to_account_info(),
+        ctx.bumps.market_asset_ata_authority,
+        ctx.accounts.seller_asset_ata.to_account_info(),
+        ctx.remaining_accounts,
+        sale_acc.sale_amount,
+    )?;
+    close_sale_escrow(
+        ctx.accounts.token_program.to_account_info(),
+        &mut ctx.accounts.market_asset_ata,
+        ctx.accounts.market_asset_ata_authority.to_account_info(),
+        ctx.bumps.market_asset_ata_authority,
+        ctx.accounts.seller.to_account_info(),
+    )?;
+    msg!("listing {} expired", sale_acc.listing_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExpireListingContext<'info> {
+    #[account(
+        mut,
+        seeds = [&sale_acc.listing_id.to_string().as_bytes()],
+        bump,
+        close = seller,
+        constraint = sale_acc.escrow_ata == market_asset_ata.key() @ MarketplaceError::SaleNotFound
+    )]
+    pub sale_acc: Account<'info, Sale>,
+    #[account(mut, token::mint = asset_mint)]
+    pub market_asset_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub asset_mint: InterfaceAccount<'info, AssetMint>,
+    /// CHECK: PDA signer of the escrow account, holds no data
+    #[account(
+        seeds = [market_asset_ata.key().as_ref()],
+        bump
+    )]
+    pub market_asset_ata_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = sale_acc.seller_asset_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub seller_asset_ata: InterfaceAccount<'info, AssetTokenAccount>,
+    /// CHECK: receives the listing rent
+    #[account(mut, address = sale_acc.seller @ MarketplaceError::NotAuthorized)]
+    pub seller: AccountInfo<'info>,
+    pub token_program: Interface<'info, AssetTokenInterface>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct UpdateListingPriceArgs {
+    pub new_price: u64,
+}
+
+pub fn update_listing_price_handler(
+    ctx: Context<UpdateListingPriceContext>,
+    args: UpdateListingPriceArgs,
+) -> Result<()> {
+    let sale_acc = &mut ctx.accounts.sale_acc;
+    require!(sale_acc.sale_state == 0, MarketplaceError::SaleNotFound);
+    require!(
+        sale_acc.seller == ctx.accounts.seller.key(),
+        MarketplaceError::NotAuthorized
+    );
+    require!(
+        sale_acc.dutch_auction.is_none(),
+        MarketplaceError::InvalidListingType
+    );
+    require!(args.new_price > 0, MarketplaceError::InvalidPrice);
+    require!(
+        ctx.accounts.marketplace.accepts_price(args.new_price),
+        MarketplaceError::ListingPolicyViolation
+    );
+    check_oracle_floor(
+        &ctx.accounts.marketplace,
+        &ctx.accounts.asset_account,
+        ctx.accounts.price_feed.as_ref(),
+        &sale_acc.payment_mint,
+        args.new_price,
+    )?;
+    sale_acc.price = args.new_price;
+    msg!(
+        "listing {} repriced to {}",
+        sale_acc.listing_id,
+        args.new_price
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateListingPriceContext<'info> {
+    #[account(
+        mut,
+        seeds = [&sale_acc.listing_id.to_string().as_bytes()],
+        bump
+    )]
+    pub sale_acc: Account<'info, Sale>,
+    #[account(address = sale_acc.marketplace)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(address = sale_acc.asset_account)]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(address = asset_account.price_feed.unwrap_or_default())]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+    pub seller: Signer<'info>,
 }
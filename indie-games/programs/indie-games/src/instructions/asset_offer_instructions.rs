@@ -0,0 +1,417 @@
+use crate::{
+    errors::marketplace_errors::*,
+    events::marketplace_events::*,
+    instructions::{
+        asset_management_instructions::transfer_asset,
+        asset_marketplace_instructions::pay_sale_proceeds,
+    },
+    state::{asset_state::*, marketplace_state::*, offer_state::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{
+        close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer as SPLTransfer,
+    },
+    token_interface::{
+        Mint as AssetMint, TokenAccount as AssetTokenAccount, TokenInterface as AssetTokenInterface,
+    },
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct MakeOfferArgs {
+    pub asset_name: String,
+    pub asset_game_id: Pubkey,
+    pub holder: Option<Pubkey>,
+    pub unit_price: u64,
+    pub amount: u64,
+    pub expires_at: i64,
+}
+
+pub fn make_offer_handler(ctx: Context<MakeOfferContext>, args: MakeOfferArgs) -> Result<()> {
+    require!(
+        ctx.accounts.asset_account.trade,
+        MarketplaceError::CantListAsset
+    );
+    require!(args.unit_price > 0, MarketplaceError::InvalidPrice);
+    require!(args.amount > 0, MarketplaceError::InvalidQuantity);
+    require!(
+        args.expires_at > Clock::get()?.unix_timestamp,
+        MarketplaceError::OfferExpired
+    );
+    let escrow_amount = args
+        .unit_price
+        .checked_mul(args.amount)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+
+    let market = &mut ctx.accounts.marketplace;
+    let offer = &mut ctx.accounts.offer;
+    offer.offer_id = market.current_listing_id;
+    offer.buyer = ctx.accounts.buyer.key();
+    offer.asset_account = ctx.accounts.asset_account.key();
+    offer.asset_mint = ctx.accounts.asset_mint.key();
+    offer.holder = args.holder;
+    offer.buyer_asset_ata = ctx.accounts.buyer_asset_ata.key();
+    offer.buyer_dsc_ata = ctx.accounts.buyer_dsc_ata.key();
+    offer.unit_price = args.unit_price;
+    offer.remaining_amount = args.amount;
+    offer.expires_at = args.expires_at;
+    offer.bump = ctx.bumps.offer;
+    market.current_listing_id = market
+        .current_listing_id
+        .checked_add(1)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+
+    let cpi_accounts = SPLTransfer {
+        from: ctx.accounts.buyer_dsc_ata.to_account_info(),
+        to: ctx.accounts.offer_vault.to_account_info(),
+        authority: ctx.accounts.buyer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    transfer(CpiContext::new(cpi_program, cpi_accounts), escrow_amount)?;
+    msg!("offer {} placed", offer.offer_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: MakeOfferArgs)]
+pub struct MakeOfferContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        init,
+        seeds = [b"offer", marketplace.current_listing_id.to_le_bytes().as_ref()],
+        bump,
+        payer = buyer,
+        space = 8 + Offer::INIT_SPACE
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+    #[account(
+        seeds = [args.asset_name.as_bytes(), args.asset_game_id.as_ref()],
+        bump,
+    )]
+    pub asset_account: Box<Account<'info, AssetData>>,
+    #[account(
+        seeds = [args.asset_game_id.as_ref(), asset_account.key().as_ref()],
+        bump,
+    )]
+    pub asset_mint: Box<InterfaceAccount<'info, AssetMint>>,
+    #[account(token::mint = asset_mint)]
+    pub buyer_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        mut,
+        token::mint = dsc_mint,
+    )]
+    pub buyer_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"mint"],
+        bump,
+        seeds::program = stable_coin::ID
+    )]
+    pub dsc_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        seeds = [b"offer_vault", offer.key().as_ref()],
+        bump,
+        payer = buyer,
+        token::mint = dsc_mint,
+        token::authority = offer,
+    )]
+    pub offer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// refunds whatever is left in the vault to the buyer and closes it
+fn refund_offer_vault<'info>(
+    offer: &Account<'info, Offer>,
+    token_program: AccountInfo<'info>,
+    offer_vault: &Account<'info, TokenAccount>,
+    buyer_dsc_ata: AccountInfo<'info>,
+    buyer: AccountInfo<'info>,
+) -> Result<()> {
+    let offer_id = offer.offer_id.to_le_bytes();
+    let seeds: &[&[&[u8]]] = &[&[b"offer", offer_id.as_ref(), &[offer.bump]]];
+    if offer_vault.amount > 0 {
+        let cpi_accounts = SPLTransfer {
+            from: offer_vault.to_account_info(),
+            to: buyer_dsc_ata,
+            authority: offer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, seeds);
+        transfer(cpi_ctx, offer_vault.amount)?;
+    }
+    let cpi_accounts = CloseAccount {
+        account: offer_vault.to_account_info(),
+        destination: buyer,
+        authority: offer.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(
+        token_program,
+        cpi_accounts,
+        seeds,
+    ))
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct AcceptOfferArgs {
+    pub quantity: u64,
+}
+
+pub fn accept_offer_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptOfferContext<'info>>,
+    args: AcceptOfferArgs,
+) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    let holder = ctx.accounts.holder.key();
+    require!(
+        Clock::get()?.unix_timestamp < offer.expires_at,
+        MarketplaceError::OfferExpired
+    );
+    require!(
+        ctx.accounts.asset_account.trade,
+        MarketplaceError::CantListAsset
+    );
+    require!(
+        offer.holder.unwrap_or(holder) == holder,
+        MarketplaceError::NotAuthorized
+    );
+    require!(args.quantity > 0, MarketplaceError::InvalidQuantity);
+    require!(
+        args.quantity <= offer.remaining_amount,
+        MarketplaceError::QuantityExceedsListing
+    );
+    let holder_ata = &ctx.accounts.holder_asset_ata;
+    let holder_ata_authority = &ctx.accounts.holder_asset_ata_authority;
+    require!(
+        holder_ata_authority.user == holder && holder_ata.owner == holder_ata_authority.key(),
+        MarketplaceError::NotAuthorized
+    );
+
+    let total_price = offer
+        .unit_price
+        .checked_mul(args.quantity)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    let proceeds = split_sale_proceeds(
+        total_price,
+        ctx.accounts.marketplace.fee_bps,
+        ctx.accounts.asset_account.royalty_bps,
+    )
+    .ok_or(MarketplaceError::ArithmeticError)?;
+    let offer_id = offer.offer_id.to_le_bytes();
+    let offer_seeds: &[&[&[u8]]] = &[&[b"offer", offer_id.as_ref(), &[offer.bump]]];
+    pay_sale_proceeds(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.offer_vault.to_account_info(),
+        offer.to_account_info(),
+        offer_seeds,
+        ctx.accounts.holder_dsc_ata.to_account_info(),
+        ctx.accounts.fee_treasury_ata.to_account_info(),
+        Some(ctx.accounts.royalty_ata.to_account_info()),
+        &proceeds,
+    )?;
+
+    let mint_key = offer.asset_mint;
+    let holder_seeds: &[&[&[u8]]] = &[&[
+        holder.as_ref(),
+        mint_key.as_ref(),
+        &[ctx.bumps.holder_asset_ata_authority],
+    ]];
+    transfer_asset(
+        ctx.accounts.asset_token_program.to_account_info(),
+        holder_ata.to_account_info(),
+        &ctx.accounts.asset_mint,
+        ctx.accounts.buyer_asset_ata.to_account_info(),
+        holder_ata_authority.to_account_info(),
+        ctx.remaining_accounts,
+        holder_seeds,
+        args.quantity,
+    )?;
+
+    emit!(SaleSettled {
+        listing_id: offer.offer_id,
+        buyer: offer.buyer,
+        seller: holder,
+        quantity: args.quantity,
+        total_price,
+        protocol_fee: proceeds.protocol_fee,
+        royalty: proceeds.royalty,
+        seller_proceeds: proceeds.seller_proceeds,
+    });
+
+    let offer = &mut ctx.accounts.offer;
+    offer.remaining_amount = offer
+        .remaining_amount
+        .checked_sub(args.quantity)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    if offer.remaining_amount == 0 {
+        ctx.accounts.offer_vault.reload()?;
+        refund_offer_vault(
+            offer,
+            ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.offer_vault,
+            ctx.accounts.buyer_dsc_ata.to_account_info(),
+            ctx.accounts.buyer.to_account_info(),
+        )?;
+        offer.close(ctx.accounts.buyer.to_account_info())?;
+        msg!("offer {} filled", offer.offer_id);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptOfferContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"offer", offer.offer_id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+    #[account(
+        mut,
+        seeds = [b"offer_vault", offer.key().as_ref()],
+        bump
+    )]
+    pub offer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>,
+    #[account(address = offer.asset_account)]
+    pub asset_account: Box<Account<'info, AssetData>>,
+    #[account(
+        address = offer.asset_mint,
+        mint::token_program = asset_token_program,
+    )]
+    pub asset_mint: Box<InterfaceAccount<'info, AssetMint>>,
+    #[account(
+        mut,
+        token::mint = asset_mint,
+    )]
+    pub holder_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        seeds = [holder.key().as_ref(), offer.asset_mint.as_ref()],
+        bump
+    )]
+    pub holder_asset_ata_authority: Box<Account<'info, AssetAuthority>>,
+    #[account(
+        mut,
+        address = offer.buyer_asset_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub buyer_asset_ata: Box<InterfaceAccount<'info, AssetTokenAccount>>,
+    #[account(
+        mut,
+        token::mint = offer_vault.mint,
+    )]
+    pub holder_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = offer.buyer_dsc_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub buyer_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = offer_vault.mint,
+        constraint = fee_treasury_ata.owner == marketplace.fee_treasury @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub fee_treasury_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = offer_vault.mint,
+        constraint = royalty_ata.owner == asset_account.royalty_recipient @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub royalty_ata: Box<Account<'info, TokenAccount>>,
+    /// CHECK: receives the offer rent once it is filled
+    #[account(mut, address = offer.buyer @ MarketplaceError::NotAuthorized)]
+    pub buyer: AccountInfo<'info>,
+    pub holder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+}
+
+pub fn cancel_offer_handler(ctx: Context<CancelOfferContext>) -> Result<()> {
+    refund_offer_vault(
+        &ctx.accounts.offer,
+        ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.offer_vault,
+        ctx.accounts.buyer_dsc_ata.to_account_info(),
+        ctx.accounts.buyer.to_account_info(),
+    )?;
+    msg!("offer {} cancelled", ctx.accounts.offer.offer_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelOfferContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"offer", offer.offer_id.to_le_bytes().as_ref()],
+        bump = offer.bump,
+        has_one = buyer @ MarketplaceError::NotAuthorized,
+        close = buyer
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+    #[account(
+        mut,
+        seeds = [b"offer_vault", offer.key().as_ref()],
+        bump
+    )]
+    pub offer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = offer.buyer_dsc_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub buyer_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn expire_offer_handler(ctx: Context<ExpireOfferContext>) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp >= ctx.accounts.offer.expires_at,
+        MarketplaceError::OfferNotExpired
+    );
+    refund_offer_vault(
+        &ctx.accounts.offer,
+        ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.offer_vault,
+        ctx.accounts.buyer_dsc_ata.to_account_info(),
+        ctx.accounts.buyer.to_account_info(),
+    )?;
+    msg!("offer {} expired", ctx.accounts.offer.offer_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExpireOfferContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"offer", offer.offer_id.to_le_bytes().as_ref()],
+        bump = offer.bump,
+        has_one = buyer @ MarketplaceError::NotAuthorized,
+        close = buyer
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+    #[account(
+        mut,
+        seeds = [b"offer_vault", offer.key().as_ref()],
+        bump
+    )]
+    pub offer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = offer.buyer_dsc_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub buyer_dsc_ata: Box<Account<'info, TokenAccount>>,
+    /// CHECK: refund destination for the offer rent
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,535 @@
+use crate::{
+    errors::{asset_errors::AssetErrors, marketplace_errors::*},
+    instructions::asset_management_instructions::transfer_asset,
+    state::{asset_state::*, marketplace_state::*, swap_state::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{
+        close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer as SPLTransfer,
+    },
+    token_interface::{
+        close_account as close_asset_account, CloseAccount as CloseAssetAccount, Mint as AssetMint,
+        TokenAccount as AssetTokenAccount, TokenInterface as AssetTokenInterface,
+    },
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct SwapItemArgs {
+    pub asset_account: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct CreateSwapArgs {
+    pub counterparty: Pubkey,
+    pub offered: Vec<SwapItemArgs>,
+    pub offered_dsc: u64,
+    pub requested: Vec<SwapItemArgs>,
+    pub requested_dsc: u64,
+}
+
+// loads an asset account, enforces its trade flag and returns the mint derived from it.
+// Swaps pay no royalty, so royalty-enforced assets stay on the marketplace
+fn tradable_asset_mint<'a>(asset_info: &'a AccountInfo<'a>, program_id: &Pubkey) -> Result<Pubkey> {
+    let asset = Account::<AssetData>::try_from(asset_info)?;
+    require!(asset.trade, MarketplaceError::CantListAsset);
+    require!(!asset.royalty_enforced, AssetErrors::RoyaltyEnforced);
+    let (mint, _) =
+        Pubkey::find_program_address(&[asset.game.as_ref(), asset_info.key.as_ref()], program_id);
+    Ok(mint)
+}
+
+fn validate_bundle(items: &[SwapItemArgs], dsc_amount: u64) -> Result<()> {
+    require!(
+        items.len() <= MAX_SWAP_ITEMS && (!items.is_empty() || dsc_amount > 0),
+        MarketplaceError::InvalidSwapBundle
+    );
+    for (i, item) in items.iter().enumerate() {
+        require!(item.amount > 0, MarketplaceError::InvalidSwapBundle);
+        require!(
+            items[..i]
+                .iter()
+                .all(|other| other.asset_account != item.asset_account),
+            MarketplaceError::InvalidSwapBundle
+        );
+    }
+    Ok(())
+}
+
+// loads an item's mint and picks whichever of the two token programs owns it
+fn load_asset_mint<'info>(
+    mint_info: &'info AccountInfo<'info>,
+    mint: &Pubkey,
+    token_program: &AccountInfo<'info>,
+    asset_token_program: &AccountInfo<'info>,
+) -> Result<(InterfaceAccount<'info, AssetMint>, AccountInfo<'info>)> {
+    require!(
+        mint_info.key() == *mint,
+        MarketplaceError::SwapAccountsMismatch
+    );
+    let program = [token_program, asset_token_program]
+        .into_iter()
+        .find(|program| program.key == mint_info.owner)
+        .ok_or(MarketplaceError::SwapAccountsMismatch)?;
+    Ok((InterfaceAccount::try_from(mint_info)?, program.clone()))
+}
+
+// moves `amount` of a holder's asset out of their AssetAuthority controlled account
+#[allow(clippy::too_many_arguments)]
+fn transfer_from_holder<'info>(
+    token_program: &AccountInfo<'info>,
+    holder: &Pubkey,
+    mint: &InterfaceAccount<'info, AssetMint>,
+    holder_ata_info: &'info AccountInfo<'info>,
+    holder_ata_authority_info: &'info AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let mint_key = mint.key();
+    let holder_ata = InterfaceAccount::<AssetTokenAccount>::try_from(holder_ata_info)?;
+    let holder_ata_authority = Account::<AssetAuthority>::try_from(holder_ata_authority_info)?;
+    let (authority_key, bump) =
+        Pubkey::find_program_address(&[holder.as_ref(), mint_key.as_ref()], program_id);
+    require!(
+        holder_ata_authority.key() == authority_key
+            && holder_ata_authority.user == *holder
+            && holder_ata.owner == authority_key
+            && holder_ata.mint == mint_key,
+        MarketplaceError::NotAuthorized
+    );
+    let seeds: &[&[&[u8]]] = &[&[holder.as_ref(), mint_key.as_ref(), &[bump]]];
+    transfer_asset(
+        token_program.clone(),
+        holder_ata_info.clone(),
+        mint,
+        to.clone(),
+        holder_ata_authority_info.clone(),
+        &[],
+        seeds,
+        amount,
+    )
+}
+
+// empties a swap-owned escrow into `to` and returns its rent to the initiator. The whole
+// balance moves, not just `item.amount`, so tokens sent to the escrow can't block the close
+fn release_swap_escrow<'info>(
+    swap: &Account<'info, Swap>,
+    token_program: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, AssetMint>,
+    escrow_info: &'info AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    initiator: &AccountInfo<'info>,
+) -> Result<()> {
+    let escrow = InterfaceAccount::<AssetTokenAccount>::try_from(escrow_info)?;
+    require!(
+        escrow.owner == swap.key() && escrow.mint == mint.key(),
+        MarketplaceError::SwapAccountsMismatch
+    );
+    let swap_id = swap.swap_id.to_le_bytes();
+    let seeds: &[&[&[u8]]] = &[&[b"swap", swap_id.as_ref(), &[swap.bump]]];
+    transfer_asset(
+        token_program.clone(),
+        escrow_info.clone(),
+        mint,
+        to.clone(),
+        swap.to_account_info(),
+        &[],
+        seeds,
+        escrow.amount,
+    )?;
+    let cpi_accounts = CloseAssetAccount {
+        account: escrow_info.clone(),
+        destination: initiator.clone(),
+        authority: swap.to_account_info(),
+    };
+    close_asset_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        cpi_accounts,
+        seeds,
+    ))
+}
+
+// same for the DSC vault, which holds `offered_dsc` plus anything sent to it since
+fn transfer_from_swap_vault<'info>(
+    swap: &Account<'info, Swap>,
+    token_program: &AccountInfo<'info>,
+    swap_vault: &Account<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    initiator: &AccountInfo<'info>,
+) -> Result<()> {
+    let swap_id = swap.swap_id.to_le_bytes();
+    let seeds: &[&[&[u8]]] = &[&[b"swap", swap_id.as_ref(), &[swap.bump]]];
+    if swap_vault.amount > 0 {
+        let cpi_accounts = SPLTransfer {
+            from: swap_vault.to_account_info(),
+            to: to.clone(),
+            authority: swap.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, seeds);
+        transfer(cpi_ctx, swap_vault.amount)?;
+    }
+    let cpi_accounts = CloseAccount {
+        account: swap_vault.to_account_info(),
+        destination: initiator.clone(),
+        authority: swap.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        cpi_accounts,
+        seeds,
+    ))
+}
+
+// the destination must be the initiator's AssetAuthority controlled account for the mint
+fn check_initiator_destination<'a>(
+    destination_info: &'a AccountInfo<'a>,
+    initiator: &Pubkey,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let destination = InterfaceAccount::<AssetTokenAccount>::try_from(destination_info)?;
+    let (authority_key, _) =
+        Pubkey::find_program_address(&[initiator.as_ref(), mint.as_ref()], program_id);
+    require!(
+        destination.owner == authority_key && destination.mint == *mint,
+        MarketplaceError::SwapAccountsMismatch
+    );
+    Ok(())
+}
+
+// remaining accounts: per offered item [asset_account, asset_mint, initiator_ata, initiator_ata_authority, escrow_ata]
+// followed by per requested item [asset_account]
+pub fn create_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateSwapContext<'info>>,
+    args: CreateSwapArgs,
+) -> Result<()> {
+    validate_bundle(&args.offered, args.offered_dsc)?;
+    validate_bundle(&args.requested, args.requested_dsc)?;
+    let initiator = ctx.accounts.initiator.key();
+    require!(
+        args.counterparty != initiator,
+        MarketplaceError::NotAuthorized
+    );
+    let remaining = ctx.remaining_accounts;
+    require!(
+        remaining.len() == args.offered.len() * 5 + args.requested.len(),
+        MarketplaceError::SwapAccountsMismatch
+    );
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let asset_token_program = ctx.accounts.asset_token_program.to_account_info();
+    let swap_key = ctx.accounts.swap.key();
+
+    let mut offered = Vec::with_capacity(args.offered.len());
+    for (item, accounts) in args.offered.iter().zip(remaining.chunks(5)) {
+        require!(
+            accounts[0].key() == item.asset_account,
+            MarketplaceError::SwapAccountsMismatch
+        );
+        let mint = tradable_asset_mint(&accounts[0], ctx.program_id)?;
+        let (mint_account, mint_program) =
+            load_asset_mint(&accounts[1], &mint, &token_program, &asset_token_program)?;
+        let escrow = InterfaceAccount::<AssetTokenAccount>::try_from(&accounts[4])?;
+        require!(
+            escrow.owner == swap_key && escrow.mint == mint,
+            MarketplaceError::SwapAccountsMismatch
+        );
+        transfer_from_holder(
+            &mint_program,
+            &initiator,
+            &mint_account,
+            &accounts[2],
+            &accounts[3],
+            &accounts[4],
+            item.amount,
+            ctx.program_id,
+        )?;
+        offered.push(SwapItem {
+            asset_account: item.asset_account,
+            mint,
+            amount: item.amount,
+        });
+    }
+    let mut requested = Vec::with_capacity(args.requested.len());
+    let requested_accounts = &remaining[args.offered.len() * 5..];
+    for (item, asset_info) in args.requested.iter().zip(requested_accounts) {
+        require!(
+            asset_info.key() == item.asset_account,
+            MarketplaceError::SwapAccountsMismatch
+        );
+        let mint = tradable_asset_mint(asset_info, ctx.program_id)?;
+        requested.push(SwapItem {
+            asset_account: item.asset_account,
+            mint,
+            amount: item.amount,
+        });
+    }
+
+    if args.offered_dsc > 0 {
+        let cpi_accounts = SPLTransfer {
+            from: ctx.accounts.initiator_dsc_ata.to_account_info(),
+            to: ctx.accounts.swap_vault.to_account_info(),
+            authority: ctx.accounts.initiator.to_account_info(),
+        };
+        transfer(
+            CpiContext::new(token_program, cpi_accounts),
+            args.offered_dsc,
+        )?;
+    }
+
+    let market = &mut ctx.accounts.marketplace;
+    let swap = &mut ctx.accounts.swap;
+    swap.swap_id = market.current_listing_id;
+    swap.initiator = initiator;
+    swap.counterparty = args.counterparty;
+    swap.initiator_dsc_ata = ctx.accounts.initiator_dsc_ata.key();
+    swap.offered = offered;
+    swap.offered_dsc = args.offered_dsc;
+    swap.requested = requested;
+    swap.requested_dsc = args.requested_dsc;
+    swap.bump = ctx.bumps.swap;
+    market.current_listing_id = market
+        .current_listing_id
+        .checked_add(1)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    msg!("swap {} opened with {}", swap.swap_id, swap.counterparty);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateSwapContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        init,
+        seeds = [b"swap", marketplace.current_listing_id.to_le_bytes().as_ref()],
+        bump,
+        payer = initiator,
+        space = 8 + Swap::INIT_SPACE
+    )]
+    pub swap: Box<Account<'info, Swap>>,
+    #[account(
+        seeds = [b"mint"],
+        bump,
+        seeds::program = stable_coin::ID
+    )]
+    pub dsc_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        seeds = [b"swap_vault", swap.key().as_ref()],
+        bump,
+        payer = initiator,
+        token::mint = dsc_mint,
+        token::authority = swap,
+    )]
+    pub swap_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = dsc_mint,
+    )]
+    pub initiator_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// remaining accounts: per requested item [asset_account, asset_mint, counterparty_ata, counterparty_ata_authority, initiator_destination_ata]
+// followed by per offered item [asset_account, asset_mint, escrow_ata, counterparty_destination_ata]
+pub fn accept_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptSwapContext<'info>>,
+) -> Result<()> {
+    let swap = &ctx.accounts.swap;
+    let counterparty = ctx.accounts.counterparty.key();
+    let remaining = ctx.remaining_accounts;
+    require!(
+        remaining.len() == swap.requested.len() * 5 + swap.offered.len() * 4,
+        MarketplaceError::SwapAccountsMismatch
+    );
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let asset_token_program = ctx.accounts.asset_token_program.to_account_info();
+    let initiator = ctx.accounts.initiator.to_account_info();
+
+    for (item, accounts) in swap.requested.iter().zip(remaining.chunks(5)) {
+        require!(
+            accounts[0].key() == item.asset_account
+                && tradable_asset_mint(&accounts[0], ctx.program_id)? == item.mint,
+            MarketplaceError::SwapAccountsMismatch
+        );
+        let (mint_account, mint_program) = load_asset_mint(
+            &accounts[1],
+            &item.mint,
+            &token_program,
+            &asset_token_program,
+        )?;
+        check_initiator_destination(&accounts[4], &swap.initiator, &item.mint, ctx.program_id)?;
+        transfer_from_holder(
+            &mint_program,
+            &counterparty,
+            &mint_account,
+            &accounts[2],
+            &accounts[3],
+            &accounts[4],
+            item.amount,
+            ctx.program_id,
+        )?;
+    }
+    let offered_accounts = &remaining[swap.requested.len() * 5..];
+    for (item, accounts) in swap.offered.iter().zip(offered_accounts.chunks(4)) {
+        require!(
+            accounts[0].key() == item.asset_account
+                && tradable_asset_mint(&accounts[0], ctx.program_id)? == item.mint,
+            MarketplaceError::SwapAccountsMismatch
+        );
+        let (mint_account, mint_program) = load_asset_mint(
+            &accounts[1],
+            &item.mint,
+            &token_program,
+            &asset_token_program,
+        )?;
+        release_swap_escrow(
+            swap,
+            &mint_program,
+            &mint_account,
+            &accounts[2],
+            &accounts[3],
+            &initiator,
+        )?;
+    }
+
+    if swap.requested_dsc > 0 {
+        let cpi_accounts = SPLTransfer {
+            from: ctx.accounts.counterparty_dsc_ata.to_account_info(),
+            to: ctx.accounts.initiator_dsc_ata.to_account_info(),
+            authority: ctx.accounts.counterparty.to_account_info(),
+        };
+        transfer(
+            CpiContext::new(token_program.clone(), cpi_accounts),
+            swap.requested_dsc,
+        )?;
+    }
+    transfer_from_swap_vault(
+        swap,
+        &token_program,
+        &ctx.accounts.swap_vault,
+        &ctx.accounts.counterparty_dsc_ata.to_account_info(),
+        &initiator,
+    )?;
+    msg!("swap {} accepted", swap.swap_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptSwapContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap", swap.swap_id.to_le_bytes().as_ref()],
+        bump = swap.bump,
+        has_one = counterparty @ MarketplaceError::NotAuthorized,
+        close = initiator
+    )]
+    pub swap: Box<Account<'info, Swap>>,
+    #[account(
+        mut,
+        seeds = [b"swap_vault", swap.key().as_ref()],
+        bump
+    )]
+    pub swap_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: receives the swap, vault and escrow rent
+    #[account(mut, address = swap.initiator @ MarketplaceError::NotAuthorized)]
+    pub initiator: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = swap.initiator_dsc_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub initiator_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = swap_vault.mint,
+    )]
+    pub counterparty_dsc_ata: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub counterparty: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+}
+
+// remaining accounts: per offered item [asset_mint, escrow_ata, initiator_destination_ata]
+pub fn cancel_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelSwapContext<'info>>,
+) -> Result<()> {
+    let swap = &ctx.accounts.swap;
+    let signer = ctx.accounts.signer.key();
+    require!(
+        signer == swap.initiator || signer == swap.counterparty,
+        MarketplaceError::NotAuthorized
+    );
+    let remaining = ctx.remaining_accounts;
+    require!(
+        remaining.len() == swap.offered.len() * 3,
+        MarketplaceError::SwapAccountsMismatch
+    );
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let asset_token_program = ctx.accounts.asset_token_program.to_account_info();
+    let initiator = ctx.accounts.initiator.to_account_info();
+    for (item, accounts) in swap.offered.iter().zip(remaining.chunks(3)) {
+        let (mint_account, mint_program) = load_asset_mint(
+            &accounts[0],
+            &item.mint,
+            &token_program,
+            &asset_token_program,
+        )?;
+        check_initiator_destination(&accounts[2], &swap.initiator, &item.mint, ctx.program_id)?;
+        release_swap_escrow(
+            swap,
+            &mint_program,
+            &mint_account,
+            &accounts[1],
+            &accounts[2],
+            &initiator,
+        )?;
+    }
+    transfer_from_swap_vault(
+        swap,
+        &token_program,
+        &ctx.accounts.swap_vault,
+        &ctx.accounts.initiator_dsc_ata.to_account_info(),
+        &initiator,
+    )?;
+    msg!("swap {} cancelled", swap.swap_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelSwapContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap", swap.swap_id.to_le_bytes().as_ref()],
+        bump = swap.bump,
+        close = initiator
+    )]
+    pub swap: Box<Account<'info, Swap>>,
+    #[account(
+        mut,
+        seeds = [b"swap_vault", swap.key().as_ref()],
+        bump
+    )]
+    pub swap_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: receives the swap, vault and escrow rent
+    #[account(mut, address = swap.initiator @ MarketplaceError::NotAuthorized)]
+    pub initiator: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = swap.initiator_dsc_ata @ MarketplaceError::InvalidPaymentAccount
+    )]
+    pub initiator_dsc_ata: Box<Account<'info, TokenAccount>>,
+    pub signer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub asset_token_program: Interface<'info, AssetTokenInterface>,
+}
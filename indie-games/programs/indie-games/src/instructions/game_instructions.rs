@@ -17,9 +17,76 @@ pub fn initialize_game_handler(
     args: InitializeGameArgs,
 ) -> Result<()> {
     let game_acc = &mut ctx.accounts.game_account;
+    game_acc.seed_name = args.name.clone();
     game_acc.name = args.name;
+    game_acc.creator = args.owner;
     game_acc.owner = args.owner;
     game_acc.description = args.description;
+    game_acc.bump = ctx.bumps.game_account;
+    game_acc.pending_owner = None;
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateGameArgs {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+pub fn update_game_handler(ctx: Context<UpdateGameContext>, args: UpdateGameArgs) -> Result<()> {
+    let game_acc = &mut ctx.accounts.game_account;
+    require!(
+        game_acc.owner == ctx.accounts.owner.key(),
+        GameErrors::UnAuthorizedOperation
+    );
+    if let Some(name) = args.name {
+        require!(
+            !name.is_empty() && name.len() <= 10,
+            GameErrors::InvalidArgs
+        );
+        game_acc.name = name;
+    }
+    if let Some(description) = args.description {
+        require!(description.len() <= 50, GameErrors::InvalidArgs);
+        game_acc.description = description;
+    }
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProposeGameOwnerArgs {
+    pub new_owner: Pubkey,
+}
+
+pub fn propose_game_owner_handler(
+    ctx: Context<UpdateGameContext>,
+    args: ProposeGameOwnerArgs,
+) -> Result<()> {
+    let game_acc = &mut ctx.accounts.game_account;
+    require!(
+        game_acc.owner == ctx.accounts.owner.key(),
+        GameErrors::UnAuthorizedOperation
+    );
+    game_acc.pending_owner = Some(args.new_owner);
+    msg!(
+        "ownership of {} proposed to {}",
+        game_acc.key(),
+        args.new_owner
+    );
+    Ok(())
+}
+
+pub fn accept_game_owner_handler(ctx: Context<AcceptGameOwnerContext>) -> Result<()> {
+    let game_acc = &mut ctx.accounts.game_account;
+    let new_owner = ctx.accounts.new_owner.key();
+    let pending_owner = game_acc.pending_owner.ok_or(GameErrors::NoPendingOwner)?;
+    require!(
+        pending_owner == new_owner,
+        GameErrors::UnAuthorizedOperation
+    );
+    game_acc.owner = new_owner;
+    game_acc.pending_owner = None;
+    msg!("ownership of {} accepted by {}", game_acc.key(), new_owner);
     Ok(())
 }
 
@@ -73,6 +140,28 @@ pub struct InitializeGameContext<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateGameContext<'info> {
+    #[account(
+        mut,
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump,
+    )]
+    pub game_account: Account<'info, GameState>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptGameOwnerContext<'info> {
+    #[account(
+        mut,
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump,
+    )]
+    pub game_account: Account<'info, GameState>,
+    pub new_owner: Signer<'info>,
+}
+
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct GrantMintAuthorityArgs {
     pub source_game_name: String,
@@ -91,8 +180,18 @@ pub fn grant_mint_authority_handler(
         asset_account.game == args.source_game_id,
         GameErrors::InvalidArgs
     );
+    // the grant is checked against the signer's roles in this game, so it must own the asset
+    require_keys_eq!(
+        asset_account.game,
+        game_account.key(),
+        GameErrors::UnAuthorizedOperation
+    );
     require!(
-        game_account.owner == callee,
+        game_account.has_role(
+            &callee,
+            ctx.accounts.signer_role.as_deref(),
+            GameRole::GRANT
+        ),
         GameErrors::UnAuthorizedOperation
     );
     let mint_auth_acc = &mut ctx.accounts.mint_authority_account;
@@ -105,8 +204,8 @@ pub fn grant_mint_authority_handler(
 #[instruction(args: GrantMintAuthorityArgs)]
 pub struct GrantMintAuthorityContext<'info> {
     #[account(
-        seeds = [game_owner.key().as_ref(),args.source_game_name.as_bytes()],
-        bump
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump
     )]
     pub game_account: Account<'info, GameState>,
     #[account(
@@ -118,10 +217,15 @@ pub struct GrantMintAuthorityContext<'info> {
         init,
         seeds = [asset_account.key().as_ref(),game_account.key().as_ref(),args.to_game_id.as_ref()],
         payer = game_owner,
-        space = 8+ AssetAuthority::INIT_SPACE,
+        space = 8 + MintAuthority::INIT_SPACE,
         bump
     )]
     pub mint_authority_account: Account<'info, MintAuthority>,
+    #[account(
+        seeds = [b"game_role",game_account.key().as_ref(),game_owner.key().as_ref()],
+        bump = signer_role.bump
+    )]
+    pub signer_role: Option<Account<'info, GameRole>>,
     #[account(mut)]
     pub game_owner: Signer<'info>,
     pub system_program: Program<'info, System>,
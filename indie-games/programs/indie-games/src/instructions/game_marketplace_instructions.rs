@@ -0,0 +1,91 @@
+use crate::{
+    errors::marketplace_errors::*,
+    instructions::asset_marketplace_instructions::{
+        apply_marketplace_config, MarketplaceConfigArgs,
+    },
+    state::{game_state::*, marketplace_state::*},
+};
+use anchor_lang::prelude::*;
+
+pub fn initialize_game_marketplace_handler(
+    ctx: Context<InitGameMarketplaceContext>,
+    args: MarketplaceConfigArgs,
+) -> Result<()> {
+    let game_account = &ctx.accounts.game_account;
+    require!(
+        game_account.has_role(
+            &ctx.accounts.authority.key(),
+            ctx.accounts.signer_role.as_deref(),
+            GameRole::MARKET_ADMIN
+        ),
+        MarketplaceError::NotAuthorized
+    );
+    let marketplace = &mut ctx.accounts.game_marketplace;
+    marketplace.current_listing_id = 0;
+    marketplace.admin = ctx.accounts.authority.key();
+    marketplace.game = Some(game_account.key());
+    apply_marketplace_config(marketplace, args)?;
+    msg!("marketplace opened for game {}", game_account.key());
+    Ok(())
+}
+
+pub fn update_game_marketplace_handler(
+    ctx: Context<UpdateGameMarketplaceContext>,
+    args: MarketplaceConfigArgs,
+) -> Result<()> {
+    require!(
+        ctx.accounts.game_account.has_role(
+            &ctx.accounts.authority.key(),
+            ctx.accounts.signer_role.as_deref(),
+            GameRole::MARKET_ADMIN
+        ),
+        MarketplaceError::NotAuthorized
+    );
+    apply_marketplace_config(&mut ctx.accounts.game_marketplace, args)
+}
+
+#[derive(Accounts)]
+pub struct InitGameMarketplaceContext<'info> {
+    #[account(
+        seeds = [game_account.creator.as_ref(), game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
+    pub game_account: Account<'info, GameState>,
+    #[account(
+        seeds = [b"game_role", game_account.key().as_ref(), authority.key().as_ref()],
+        bump = signer_role.bump
+    )]
+    pub signer_role: Option<Account<'info, GameRole>>,
+    #[account(
+        init,
+        seeds = [b"marketplace", game_account.key().as_ref()],
+        bump,
+        space = 8 + Marketplace::INIT_SPACE,
+        payer = authority
+    )]
+    pub game_marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGameMarketplaceContext<'info> {
+    #[account(
+        seeds = [game_account.creator.as_ref(), game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
+    pub game_account: Account<'info, GameState>,
+    #[account(
+        seeds = [b"game_role", game_account.key().as_ref(), authority.key().as_ref()],
+        bump = signer_role.bump
+    )]
+    pub signer_role: Option<Account<'info, GameRole>>,
+    #[account(
+        mut,
+        seeds = [b"marketplace", game_account.key().as_ref()],
+        bump,
+    )]
+    pub game_marketplace: Account<'info, Marketplace>,
+    pub authority: Signer<'info>,
+}
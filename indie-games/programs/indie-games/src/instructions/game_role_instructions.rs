@@ -0,0 +1,113 @@
+use crate::{errors::game_errors::*, state::game_state::*};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetGameRoleArgs {
+    pub member: Pubkey,
+    pub roles: u8,
+}
+
+pub fn add_game_role_handler(
+    ctx: Context<AddGameRoleContext>,
+    args: SetGameRoleArgs,
+) -> Result<()> {
+    let game_account = &ctx.accounts.game_account;
+    require!(
+        game_account.owner == ctx.accounts.owner.key(),
+        GameErrors::UnAuthorizedOperation
+    );
+    require!(
+        args.roles != 0 && args.roles & !GameRole::ALL == 0,
+        GameErrors::InvalidArgs
+    );
+    let role_account = &mut ctx.accounts.role_account;
+    role_account.game = game_account.key();
+    role_account.member = args.member;
+    role_account.roles = args.roles;
+    role_account.bump = ctx.bumps.role_account;
+    msg!("granted roles {} to {}", args.roles, args.member);
+    Ok(())
+}
+
+pub fn update_game_role_handler(
+    ctx: Context<UpdateGameRoleContext>,
+    args: SetGameRoleArgs,
+) -> Result<()> {
+    require!(
+        ctx.accounts.game_account.owner == ctx.accounts.owner.key(),
+        GameErrors::UnAuthorizedOperation
+    );
+    require!(
+        args.roles != 0 && args.roles & !GameRole::ALL == 0,
+        GameErrors::InvalidArgs
+    );
+    let role_account = &mut ctx.accounts.role_account;
+    role_account.roles = args.roles;
+    msg!("updated roles of {} to {}", args.member, args.roles);
+    Ok(())
+}
+
+pub fn revoke_game_role_handler(ctx: Context<RevokeGameRoleContext>) -> Result<()> {
+    require!(
+        ctx.accounts.game_account.owner == ctx.accounts.owner.key(),
+        GameErrors::UnAuthorizedOperation
+    );
+    msg!("revoked roles of {}", ctx.accounts.role_account.member);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetGameRoleArgs)]
+pub struct AddGameRoleContext<'info> {
+    #[account(
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
+    pub game_account: Account<'info, GameState>,
+    #[account(
+        init,
+        seeds = [b"game_role",game_account.key().as_ref(),args.member.as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + GameRole::INIT_SPACE
+    )]
+    pub role_account: Account<'info, GameRole>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetGameRoleArgs)]
+pub struct UpdateGameRoleContext<'info> {
+    #[account(
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
+    pub game_account: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"game_role",game_account.key().as_ref(),args.member.as_ref()],
+        bump = role_account.bump
+    )]
+    pub role_account: Account<'info, GameRole>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeGameRoleContext<'info> {
+    #[account(
+        seeds = [game_account.creator.as_ref(),game_account.seed_name.as_bytes()],
+        bump = game_account.bump
+    )]
+    pub game_account: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [b"game_role",game_account.key().as_ref(),role_account.member.as_ref()],
+        bump = role_account.bump,
+        close = owner
+    )]
+    pub role_account: Account<'info, GameRole>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
@@ -1,4 +1,10 @@
+pub mod asset_auction_instructions;
 pub mod asset_initialize_instructions;
 pub mod asset_management_instructions;
 pub mod asset_marketplace_instructions;
-pub mod game_instructions;
\ No newline at end of file
+pub mod asset_offer_instructions;
+pub mod asset_swap_instructions;
+pub mod game_instructions;
+pub mod game_marketplace_instructions;
+pub mod game_role_instructions;
+pub mod price_feed_instructions;
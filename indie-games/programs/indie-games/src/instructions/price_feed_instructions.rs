@@ -0,0 +1,82 @@
+use crate::{
+    errors::{asset_errors::AssetErrors, oracle_errors::*},
+    state::{asset_state::*, game_state::*},
+};
+use anchor_lang::prelude::*;
+use stable_coin::state::oracle_state::PriceFeed;
+
+// the asset's feed when it has one, failing if it wasn't passed or has gone stale
+pub(crate) fn fresh_price_feed<'a>(
+    asset: &AssetData,
+    price_feed: Option<&'a Account<PriceFeed>>,
+) -> Result<Option<&'a PriceFeed>> {
+    let Some(feed_key) = asset.price_feed else {
+        return Ok(None);
+    };
+    let feed = price_feed
+        .filter(|feed| feed.key() == feed_key)
+        .ok_or(OracleError::MissingPriceFeed)?;
+    require!(
+        feed.is_fresh(Clock::get()?.unix_timestamp),
+        OracleError::StalePrice
+    );
+    Ok(Some(feed))
+}
+
+// DSC base units per token health checks run at: the bottom of the feed's band so a noisy
+// feed can't force a liquidation
+pub(crate) fn health_price(
+    asset: &AssetData,
+    price_feed: Option<&Account<PriceFeed>>,
+) -> Result<u64> {
+    match fresh_price_feed(asset, price_feed)? {
+        Some(feed) => Ok(feed.collateral_price()),
+        None => asset
+            .base_price()
+            .ok_or(error!(AssetErrors::CollateralOverflow)),
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct SetAssetPriceFeedArgs {
+    pub asset_name: String,
+}
+
+// points the asset's valuations at a stable-coin feed the game owner runs for it
+pub fn set_asset_price_feed_handler(
+    ctx: Context<SetAssetPriceFeedContext>,
+    _args: SetAssetPriceFeedArgs,
+) -> Result<()> {
+    let price_feed = &ctx.accounts.price_feed;
+    require_keys_eq!(
+        price_feed.subject,
+        ctx.accounts.asset_account.key(),
+        OracleError::FeedMismatch
+    );
+    require_keys_eq!(
+        price_feed.authority,
+        ctx.accounts.authority.key(),
+        OracleError::FeedMismatch
+    );
+    ctx.accounts.asset_account.price_feed = Some(price_feed.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetAssetPriceFeedArgs)]
+pub struct SetAssetPriceFeedContext<'info> {
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(
+        mut,
+        seeds = [args.asset_name.as_bytes(), game_account.key().as_ref()],
+        bump
+    )]
+    pub asset_account: Account<'info, AssetData>,
+    #[account(
+        seeds = [game_account.creator.as_ref(), game_account.seed_name.as_bytes()],
+        bump = game_account.bump,
+        constraint = game_account.owner == authority.key() @ OracleError::NotAuthorized
+    )]
+    pub game_account: Account<'info, GameState>,
+    pub authority: Signer<'info>,
+}
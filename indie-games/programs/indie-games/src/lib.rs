@@ -1,15 +1,19 @@
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
 // use state::*;
 // use errors::*;
 use instructions::{
-    asset_initialize_instructions::*, asset_management_instructions::*,
-    asset_marketplace_instructions::*, game_instructions::*,
+    asset_auction_instructions::*, asset_initialize_instructions::*,
+    asset_management_instructions::*, asset_marketplace_instructions::*,
+    asset_offer_instructions::*, asset_swap_instructions::*, game_instructions::*,
+    game_marketplace_instructions::*, game_role_instructions::*, price_feed_instructions::*,
 };
+use state::asset_state::CollateralStatus;
 
 declare_id!("FohwxEdiTeT3ZY4r7rXH4dctCLTbA3S1pc8ibibHWaVa");
 
@@ -31,6 +35,36 @@ pub mod indie_games {
         initialize_game_handler(ctx, args)
     }
 
+    pub fn update_game(ctx: Context<UpdateGameContext>, args: UpdateGameArgs) -> Result<()> {
+        update_game_handler(ctx, args)
+    }
+
+    pub fn propose_game_owner(
+        ctx: Context<UpdateGameContext>,
+        args: ProposeGameOwnerArgs,
+    ) -> Result<()> {
+        propose_game_owner_handler(ctx, args)
+    }
+
+    pub fn accept_game_owner(ctx: Context<AcceptGameOwnerContext>) -> Result<()> {
+        accept_game_owner_handler(ctx)
+    }
+
+    pub fn add_game_role(ctx: Context<AddGameRoleContext>, args: SetGameRoleArgs) -> Result<()> {
+        add_game_role_handler(ctx, args)
+    }
+
+    pub fn update_game_role(
+        ctx: Context<UpdateGameRoleContext>,
+        args: SetGameRoleArgs,
+    ) -> Result<()> {
+        update_game_role_handler(ctx, args)
+    }
+
+    pub fn revoke_game_role(ctx: Context<RevokeGameRoleContext>) -> Result<()> {
+        revoke_game_role_handler(ctx)
+    }
+
     pub fn initialize_assets(
         ctx: Context<InitializeAssetDataContext>,
         args: InitializeAssetDataArgs,
@@ -57,6 +91,20 @@ pub mod indie_games {
         mint_authorized_asset_handler(ctx, args)
     }
 
+    pub fn initialize_collateral_vault(
+        ctx: Context<InitCollateralVaultContext>,
+        args: InitCollateralVaultArgs,
+    ) -> Result<()> {
+        init_collateral_vault_handler(ctx, args)
+    }
+
+    pub fn get_collateral_status(
+        ctx: Context<CollateralStatusContext>,
+        args: CollateralStatusArgs,
+    ) -> Result<CollateralStatus> {
+        get_collateral_status_handler(ctx, args)
+    }
+
     pub fn initialize_assets_ata(
         ctx: Context<InitAssetATAContext>,
         args: InitAssetATAArgs,
@@ -64,22 +112,171 @@ pub mod indie_games {
         init_asset_ata_handler(ctx, args)
     }
 
-    pub fn initialize_marketplace(ctx: Context<InitMarketplaceContext>) -> Result<()> {
-        initialize_marketplace_handler(ctx)
+    pub fn initialize_marketplace(
+        ctx: Context<InitMarketplaceContext>,
+        args: MarketplaceConfigArgs,
+    ) -> Result<()> {
+        initialize_marketplace_handler(ctx, args)
     }
 
-    pub fn list_asset(ctx: Context<ListForSaleContext>, args: ListAssetArgs) -> Result<()> {
+    pub fn update_marketplace_config(
+        ctx: Context<UpdateMarketplaceConfigContext>,
+        args: MarketplaceConfigArgs,
+    ) -> Result<()> {
+        update_marketplace_config_handler(ctx, args)
+    }
+
+    pub fn initialize_game_marketplace(
+        ctx: Context<InitGameMarketplaceContext>,
+        args: MarketplaceConfigArgs,
+    ) -> Result<()> {
+        initialize_game_marketplace_handler(ctx, args)
+    }
+
+    pub fn update_game_marketplace(
+        ctx: Context<UpdateGameMarketplaceContext>,
+        args: MarketplaceConfigArgs,
+    ) -> Result<()> {
+        update_game_marketplace_handler(ctx, args)
+    }
+
+    pub fn list_asset<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListForSaleContext<'info>>,
+        args: ListAssetArgs,
+    ) -> Result<()> {
         list_for_sale_handler(ctx, args)
     }
 
-    pub fn buy_from_marketplace(ctx: Context<BuyFromSaleContext>) -> Result<()> {
-        buy_from_sale_handler(ctx)
+    pub fn buy_from_marketplace<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyFromSaleContext<'info>>,
+        args: BuyFromSaleArgs,
+    ) -> Result<()> {
+        buy_from_sale_handler(ctx, args)
+    }
+
+    pub fn cancel_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelListingContext<'info>>,
+    ) -> Result<()> {
+        cancel_listing_handler(ctx)
+    }
+
+    pub fn update_listing_price(
+        ctx: Context<UpdateListingPriceContext>,
+        args: UpdateListingPriceArgs,
+    ) -> Result<()> {
+        update_listing_price_handler(ctx, args)
+    }
+
+    pub fn expire_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExpireListingContext<'info>>,
+    ) -> Result<()> {
+        expire_listing_handler(ctx)
+    }
+
+    pub fn create_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateAuctionContext<'info>>,
+        args: CreateAuctionArgs,
+    ) -> Result<()> {
+        create_auction_handler(ctx, args)
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBidContext>, args: PlaceBidArgs) -> Result<()> {
+        place_bid_handler(ctx, args)
+    }
+
+    pub fn settle_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleAuctionContext<'info>>,
+    ) -> Result<()> {
+        settle_auction_handler(ctx)
+    }
+
+    pub fn cancel_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelAuctionContext<'info>>,
+    ) -> Result<()> {
+        cancel_auction_handler(ctx)
+    }
+
+    pub fn make_offer(ctx: Context<MakeOfferContext>, args: MakeOfferArgs) -> Result<()> {
+        make_offer_handler(ctx, args)
+    }
+
+    pub fn accept_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptOfferContext<'info>>,
+        args: AcceptOfferArgs,
+    ) -> Result<()> {
+        accept_offer_handler(ctx, args)
+    }
+
+    pub fn cancel_offer(ctx: Context<CancelOfferContext>) -> Result<()> {
+        cancel_offer_handler(ctx)
+    }
+
+    pub fn expire_offer(ctx: Context<ExpireOfferContext>) -> Result<()> {
+        expire_offer_handler(ctx)
     }
 
-    pub fn transfer_assets(
-        ctx: Context<TransferAssetContext>,
+    pub fn create_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateSwapContext<'info>>,
+        args: CreateSwapArgs,
+    ) -> Result<()> {
+        create_swap_handler(ctx, args)
+    }
+
+    pub fn accept_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptSwapContext<'info>>,
+    ) -> Result<()> {
+        accept_swap_handler(ctx)
+    }
+
+    pub fn cancel_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelSwapContext<'info>>,
+    ) -> Result<()> {
+        cancel_swap_handler(ctx)
+    }
+
+    pub fn lock_asset_supply(
+        ctx: Context<LockAssetSupplyContext>,
+        args: LockAssetSupplyArgs,
+    ) -> Result<()> {
+        lock_asset_supply_handler(ctx, args)
+    }
+
+    pub fn burn_asset(ctx: Context<BurnAssetContext>, args: BurnAssetArgs) -> Result<()> {
+        burn_asset_handler(ctx, args)
+    }
+
+    pub fn clawback_asset(
+        ctx: Context<ClawbackAssetContext>,
+        args: ClawbackAssetArgs,
+    ) -> Result<()> {
+        clawback_asset_handler(ctx, args)
+    }
+
+    pub fn liquidate_asset_collateral(
+        ctx: Context<LiquidateAssetContext>,
+        args: LiquidateAssetArgs,
+    ) -> Result<()> {
+        liquidate_asset_collateral_handler(ctx, args)
+    }
+
+    pub fn refresh_asset_health(
+        ctx: Context<RefreshAssetHealthContext>,
+        args: RefreshAssetHealthArgs,
+    ) -> Result<()> {
+        refresh_asset_health_handler(ctx, args)
+    }
+
+    pub fn transfer_assets<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TransferAssetContext<'info>>,
         args: TransferAssetArgs,
     ) -> Result<()> {
         transfer_assets_handler(ctx, args)
     }
+
+    pub fn set_asset_price_feed(
+        ctx: Context<SetAssetPriceFeedContext>,
+        args: SetAssetPriceFeedArgs,
+    ) -> Result<()> {
+        set_asset_price_feed_handler(ctx, args)
+    }
 }
@@ -1,5 +1,17 @@
 use anchor_lang::prelude::*;
 
+use super::marketplace_state::MAX_BPS;
+
+pub const DSC_DECIMALS: u32 = 6;
+pub const ASSET_DECIMALS: u32 = 0;
+// premium over face value a liquidator earns on both repaying and burning
+pub const LIQUIDATION_BONUS_BPS: u64 = 500;
+
+// program every royalty-enforced asset mint points its transfer hook at
+pub mod asset_transfer_hook {
+    anchor_lang::declare_id!("39xrwBwQBHwaanFLctMeiZaT5AZ75sQhFuboXx4wGQ3p");
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct AssetData {
@@ -14,13 +26,85 @@ pub struct AssetData {
     pub score: u8,
     pub trade: bool,
     pub collateral_option: bool,
+    // basis points of `price`, 15_000 locks 1.5 DSC per DSC of price
     pub collateral_ratio: u64,
+    pub royalty_bps: u16,
+    pub royalty_recipient: Pubkey,
+    // Token-2022 mint with a transfer hook, so raw transfers can't skip the marketplace
+    pub royalty_enforced: bool,
+    pub max_supply: Option<u64>,
+    // cap on what a single holder can ever be minted, tracked in `WalletMintCount`
+    pub max_per_wallet: Option<u64>,
+    pub supply_locked: bool,
+    // DSC held in the asset's collateral vault and the supply it backs
+    pub total_collateral: u64,
+    pub collateralized_supply: u64,
+    // once set, valuations read this feed instead of `price`
+    pub price_feed: Option<Pubkey>,
+    // set by a liquidation that left the vault under-backed, blocks minting until restored
+    pub unhealthy: bool,
+}
+
+impl AssetData {
+    // the creator-set `price` in DSC base units, the unit price feeds quote in
+    pub fn base_price(&self) -> Option<u64> {
+        self.price.checked_mul(10u64.checked_pow(DSC_DECIMALS)?)
+    }
+
+    // collateral the backed supply should hold at `price` for the asset's ratio
+    pub fn required_collateral(&self, price: u64) -> Option<u64> {
+        collateral_deposit(price, self.collateral_ratio, self.collateralized_supply)
+    }
+
+    pub fn is_under_collateralized(&self, price: u64) -> Option<bool> {
+        Some(self.total_collateral < self.required_collateral(price)?)
+    }
+
+    // DSC base units the vault is missing at `price`, the most a liquidation can repay
+    pub fn collateral_shortfall(&self, price: u64) -> Option<u64> {
+        Some(
+            self.required_collateral(price)?
+                .saturating_sub(self.total_collateral),
+        )
+    }
+
+    // DSC base units paid for burning `amount` backed tokens: their value at `price` plus
+    // `LIQUIDATION_BONUS_BPS`, so the liquidator takes collateral at a discount to the
+    // oracle. Capped at what the vault holds
+    pub fn liquidation_payout(&self, price: u64, amount: u64) -> Option<u64> {
+        let at_discount =
+            collateral_deposit(price, MAX_BPS as u64 + LIQUIDATION_BONUS_BPS, amount)?;
+        Some(at_discount.min(self.total_collateral))
+    }
+
+    // re-derives `unhealthy`, called wherever collateral or backed supply moves
+    pub fn refresh_health(&mut self, price: u64) -> Option<bool> {
+        self.unhealthy = self.is_under_collateralized(price)?;
+        Some(self.unhealthy)
+    }
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct AssetAuthority {
-    pub user: Pubkey
+    pub user: Pubkey,
+}
+
+// returned by `get_collateral_status` for clients to simulate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CollateralStatus {
+    pub total_collateral: u64,
+    pub collateralized_supply: u64,
+    // DSC base units backing one asset token, rounded down
+    pub collateral_per_token: u64,
+    pub vault_balance: u64,
+}
+
+// seeded by `b"wallet_mint"`, the asset account and the holder
+#[account]
+#[derive(InitSpace)]
+pub struct WalletMintCount {
+    pub minted: u64,
 }
 
 #[account]
@@ -29,3 +113,95 @@ pub struct MintAuthority {
     pub user: Pubkey,
     pub asset_account: Pubkey,
 }
+
+// DSC base units locked when minting `amount` tokens of an asset priced at `price` DSC base
+// units per whole token
+pub fn collateral_deposit(price: u64, collateral_ratio_bps: u64, amount: u64) -> Option<u64> {
+    let scale = 10u128.checked_pow(ASSET_DECIMALS)?;
+    let required = (amount as u128)
+        .checked_mul(price as u128)?
+        .checked_mul(collateral_ratio_bps as u128)?;
+    u64::try_from(required / (MAX_BPS as u128 * scale)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deposit_is_price_times_amount_at_full_ratio() {
+        assert_eq!(collateral_deposit(10_000_000, 10_000, 1), Some(10_000_000));
+        assert_eq!(collateral_deposit(10_000_000, 10_000, 3), Some(30_000_000));
+    }
+
+    #[test]
+    fn base_price_scales_whole_dsc_to_base_units() {
+        assert_eq!(backed_asset(0, 0).base_price(), Some(1_000_000));
+    }
+
+    #[test]
+    fn ratio_keeps_basis_point_precision() {
+        assert_eq!(collateral_deposit(10_000_000, 15_000, 1), Some(15_000_000));
+        assert_eq!(collateral_deposit(10_000_000, 5_000, 1), Some(5_000_000));
+        assert_eq!(collateral_deposit(1_000_000, 1, 1), Some(100));
+    }
+
+    #[test]
+    fn zero_ratio_or_amount_needs_no_collateral() {
+        assert_eq!(collateral_deposit(10_000_000, 0, 5), Some(0));
+        assert_eq!(collateral_deposit(10_000_000, 15_000, 0), Some(0));
+    }
+
+    #[test]
+    fn overflow_is_reported_instead_of_panicking() {
+        assert_eq!(collateral_deposit(u64::MAX, 15_000, u64::MAX), None);
+        assert_eq!(collateral_deposit(u64::MAX, 10_000, 2), None);
+    }
+
+    fn backed_asset(total_collateral: u64, collateralized_supply: u64) -> AssetData {
+        AssetData {
+            game: Pubkey::default(),
+            name: String::new(),
+            symbol: String::new(),
+            uri: String::new(),
+            price: 1,
+            score: 0,
+            trade: true,
+            collateral_option: true,
+            collateral_ratio: 15_000,
+            royalty_bps: 0,
+            royalty_recipient: Pubkey::default(),
+            royalty_enforced: false,
+            max_supply: None,
+            max_per_wallet: None,
+            supply_locked: false,
+            total_collateral,
+            collateralized_supply,
+            price_feed: None,
+            unhealthy: true,
+        }
+    }
+
+    #[test]
+    fn burning_pays_the_full_bonus_on_thin_backing() {
+        // 0.5 DSC behind each token priced at 1 DSC
+        let asset = backed_asset(5_000_000, 10);
+        assert_eq!(asset.liquidation_payout(1_000_000, 2), Some(2_100_000));
+    }
+
+    #[test]
+    fn payout_never_exceeds_the_vault() {
+        let asset = backed_asset(1_000_000, 10);
+        assert_eq!(asset.liquidation_payout(1_000_000, 2), Some(1_000_000));
+    }
+
+    #[test]
+    fn shortfall_is_what_restores_the_ratio() {
+        // 150% of 10 tokens at 1 DSC needs 15 DSC
+        let mut asset = backed_asset(5_000_000, 10);
+        assert_eq!(asset.collateral_shortfall(1_000_000), Some(10_000_000));
+        asset.total_collateral += 10_000_000;
+        assert_eq!(asset.refresh_health(1_000_000), Some(false));
+        assert_eq!(asset.collateral_shortfall(1_000_000), Some(0));
+    }
+}
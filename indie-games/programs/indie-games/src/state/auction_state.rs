@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Auction {
+    pub auction_id: u64,
+    pub seller: Pubkey,
+    pub asset_account: Pubkey,
+    pub asset_mint: Pubkey,
+    pub escrow_ata: Pubkey,
+    pub seller_asset_ata: Pubkey,
+    pub dsc_credit_ata: Pubkey,
+    pub amount: u64,
+    pub reserve_price: u64,
+    pub min_bid_increment: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub highest_bid: u64,
+    pub highest_bidder: Option<Pubkey>,
+    // where the asset goes if the current top bid wins
+    pub highest_bidder_asset_ata: Pubkey,
+    pub bump: u8,
+}
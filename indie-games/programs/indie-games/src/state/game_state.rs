@@ -7,5 +7,44 @@ pub struct GameState {
     #[max_len(10)]
     pub name: String,
     #[max_len(50)]
-    pub description: String
-}
\ No newline at end of file
+    pub description: String,
+    // PDA seeds fixed at creation, so renames and owner handovers keep the game key stable
+    pub creator: Pubkey,
+    #[max_len(10)]
+    pub seed_name: String,
+    pub bump: u8,
+    pub pending_owner: Option<Pubkey>,
+}
+
+impl GameState {
+    // the owner is an implicit superuser, everyone else needs a role account carrying the flag
+    pub fn has_role(&self, member: &Pubkey, role: Option<&GameRole>, flag: u8) -> bool {
+        if *member == self.owner {
+            return true;
+        }
+        role.is_some_and(|r| r.member == *member && r.has(flag))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct GameRole {
+    pub game: Pubkey,
+    pub member: Pubkey,
+    pub roles: u8,
+    pub bump: u8,
+}
+
+impl GameRole {
+    pub const CREATE_ASSET: u8 = 1 << 0;
+    pub const MINT: u8 = 1 << 1;
+    pub const GRANT: u8 = 1 << 2;
+    pub const MARKET_ADMIN: u8 = 1 << 3;
+    pub const TREASURY: u8 = 1 << 4;
+    pub const ALL: u8 =
+        Self::CREATE_ASSET | Self::MINT | Self::GRANT | Self::MARKET_ADMIN | Self::TREASURY;
+
+    pub fn has(&self, flag: u8) -> bool {
+        self.roles & flag == flag
+    }
+}
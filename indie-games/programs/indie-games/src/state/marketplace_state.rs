@@ -1,17 +1,156 @@
 use anchor_lang::prelude::*;
 
+pub const MAX_BPS: u16 = 10_000;
+pub const MAX_PAYMENT_MINTS: usize = 8;
+// payment mint of listings settled in lamports rather than an SPL token
+pub const NATIVE_SOL_PAYMENT: Pubkey = anchor_lang::system_program::ID;
+// the stable coin's `b"mint"` PDA, the only payment mint priced against the oracle
+pub const DSC_MINT: Pubkey = pubkey!("3x31pFXzTd96NHDyZwPvWfpmDcbWsyRUm3vJ9Fe7Gaku");
+
+// the global venue is seeded `b"marketplace"`, game venues add the game key
 #[account]
 #[derive(InitSpace)]
-pub struct Marketplace{
-    pub current_listing_id:u64,
+pub struct Marketplace {
+    // only advanced on the global venue, which allocates ids for every book
+    pub current_listing_id: u64,
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    // owner of the token accounts that collect protocol fees
+    pub fee_treasury: Pubkey,
+    pub game: Option<Pubkey>,
+    // mints sellers may price listings in, `NATIVE_SOL_PAYMENT` allows plain SOL
+    #[max_len(MAX_PAYMENT_MINTS)]
+    pub allowed_payment_mints: Vec<Pubkey>,
+    pub min_price: u64,
+    // zero means no upper bound
+    pub max_price: u64,
+    pub allow_foreign_assets: bool,
+    // DSC listings of fed assets must ask at least this share of the feed price, zero disables
+    pub oracle_floor_bps: u16,
+}
+
+impl Marketplace {
+    pub fn accepts_price(&self, price: u64) -> bool {
+        price >= self.min_price && (self.max_price == 0 || price <= self.max_price)
+    }
+
+    pub fn accepts_asset(&self, asset_game: &Pubkey) -> bool {
+        match self.game {
+            Some(game) => self.allow_foreign_assets || game == *asset_game,
+            None => true,
+        }
+    }
+
+    pub fn accepts_payment_mint(&self, mint: &Pubkey) -> bool {
+        self.allowed_payment_mints.contains(mint)
+    }
 }
 
 #[account]
 #[derive(InitSpace)]
-pub struct Sale{
-    pub listing_id:u64,
+pub struct Sale {
+    pub listing_id: u64,
     pub price: u64,
     pub sale_state: u8,
-    pub sale_amount:u64,
-    pub dsc_credit_ata: Pubkey
-}
\ No newline at end of file
+    pub sale_amount: u64,
+    pub dsc_credit_ata: Pubkey,
+    pub seller: Pubkey,
+    pub escrow_ata: Pubkey,
+    pub asset_account: Pubkey,
+    // set for descending-price listings, `price` then holds the start price
+    pub dutch_auction: Option<DutchAuction>,
+    pub seller_asset_ata: Pubkey,
+    pub expires_at: Option<i64>,
+    pub marketplace: Pubkey,
+    pub payment_mint: Pubkey,
+}
+
+impl Sale {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    pub fn is_paid_in_sol(&self) -> bool {
+        self.payment_mint == NATIVE_SOL_PAYMENT
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PriceCurve {
+    Linear,
+    Stepwise { step_duration: i64 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct DutchAuction {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub decay_duration: i64,
+    pub curve: PriceCurve,
+}
+
+impl DutchAuction {
+    pub fn is_valid(&self) -> bool {
+        let curve_ok = match self.curve {
+            PriceCurve::Linear => true,
+            PriceCurve::Stepwise { step_duration } => {
+                step_duration > 0 && step_duration <= self.decay_duration
+            }
+        };
+        self.floor_price > 0
+            && self.start_price > self.floor_price
+            && self.decay_duration > 0
+            && curve_ok
+    }
+
+    // per-unit price at `now`, decaying from start to floor over `decay_duration`
+    pub fn price_at(&self, now: i64) -> Option<u64> {
+        let mut elapsed = now
+            .checked_sub(self.start_time)?
+            .clamp(0, self.decay_duration);
+        if let PriceCurve::Stepwise { step_duration } = self.curve {
+            elapsed -= elapsed % step_duration;
+        }
+        let range = self.start_price.checked_sub(self.floor_price)? as u128;
+        let decayed = range
+            .checked_mul(elapsed as u128)?
+            .checked_div(self.decay_duration as u128)?;
+        u64::try_from((self.start_price as u128).checked_sub(decayed)?).ok()
+    }
+}
+
+pub struct SaleProceeds {
+    pub protocol_fee: u64,
+    pub royalty: u64,
+    pub seller_proceeds: u64,
+}
+
+// fee and royalty are each rounded down, the seller keeps the remainder including any dust
+pub fn split_sale_proceeds(total: u64, fee_bps: u16, royalty_bps: u16) -> Option<SaleProceeds> {
+    let bps_cut = |bps: u16| -> Option<u64> {
+        let cut = (total as u128)
+            .checked_mul(bps as u128)?
+            .checked_div(MAX_BPS as u128)?;
+        u64::try_from(cut).ok()
+    };
+    let protocol_fee = bps_cut(fee_bps)?;
+    let royalty = bps_cut(royalty_bps)?;
+    let seller_proceeds = total.checked_sub(protocol_fee)?.checked_sub(royalty)?;
+    Some(SaleProceeds {
+        protocol_fee,
+        royalty,
+        seller_proceeds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dsc_mint_is_the_stable_coin_mint_pda() {
+        let (mint, _) = Pubkey::find_program_address(&[b"mint"], &stable_coin::ID);
+        assert_eq!(DSC_MINT, mint);
+    }
+}
@@ -1,3 +1,6 @@
 pub mod asset_state;
+pub mod auction_state;
 pub mod game_state;
-pub mod marketplace_state;
\ No newline at end of file
+pub mod marketplace_state;
+pub mod offer_state;
+pub mod swap_state;
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub offer_id: u64,
+    pub buyer: Pubkey,
+    pub asset_account: Pubkey,
+    pub asset_mint: Pubkey,
+    // None makes the offer collection-wide, any holder of the asset can fill it
+    pub holder: Option<Pubkey>,
+    pub buyer_asset_ata: Pubkey,
+    pub buyer_dsc_ata: Pubkey,
+    pub unit_price: u64,
+    pub remaining_amount: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
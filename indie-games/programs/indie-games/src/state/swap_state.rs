@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_SWAP_ITEMS: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct SwapItem {
+    pub asset_account: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Swap {
+    pub swap_id: u64,
+    pub initiator: Pubkey,
+    pub counterparty: Pubkey,
+    pub initiator_dsc_ata: Pubkey,
+    #[max_len(MAX_SWAP_ITEMS)]
+    pub offered: Vec<SwapItem>,
+    pub offered_dsc: u64,
+    #[max_len(MAX_SWAP_ITEMS)]
+    pub requested: Vec<SwapItem>,
+    pub requested_dsc: u64,
+    pub bump: u8,
+}
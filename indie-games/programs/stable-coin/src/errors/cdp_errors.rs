@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum CdpError {
+    #[msg("Signer is not authorized for this operation")]
+    NotAuthorized,
+    #[msg("Collateral config is invalid")]
+    InvalidConfig,
+    #[msg("Amount must be non-zero")]
+    InvalidAmount,
+    #[msg("Collateral accounts are missing or invalid")]
+    InvalidCollateralAccount,
+    #[msg("Position would fall below the collateral ratio")]
+    Undercollateralized,
+    #[msg("Withdrawal exceeds the position's collateral")]
+    InsufficientCollateral,
+    #[msg("Repayment exceeds the position's debt")]
+    DebtUnderflow,
+    #[msg("Position math overflowed")]
+    MathOverflow,
+    #[msg("Position is healthy, nothing to liquidate")]
+    PositionHealthy,
+    #[msg("Repayment exceeds the close factor")]
+    ExceedsCloseFactor,
+    #[msg("Collateral type is disabled")]
+    CollateralDisabled,
+    #[msg("Minting would exceed the collateral type's debt ceiling")]
+    DebtCeilingExceeded,
+    #[msg("Minting would exceed the global debt ceiling")]
+    GlobalDebtCeilingExceeded,
+}
@@ -0,0 +1,3 @@
+pub mod cdp_errors;
+pub mod oracle_errors;
+pub mod psm_errors;
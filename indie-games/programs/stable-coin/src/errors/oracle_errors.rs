@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Signer can't manage or update this price feed")]
+    NotAuthorized,
+    #[msg("Price feed config is invalid")]
+    InvalidFeedConfig,
+    #[msg("Price must be non-zero")]
+    InvalidPrice,
+    #[msg("Price feed is stale")]
+    StalePrice,
+}
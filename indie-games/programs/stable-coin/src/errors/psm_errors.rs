@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum PsmError {
+    #[msg("Reserve config is invalid")]
+    InvalidReserveConfig,
+    #[msg("Stable mint must have the same decimals as DSC")]
+    InvalidStableMint,
+    #[msg("Amount must exceed the swap fee")]
+    InvalidAmount,
+    #[msg("Swap would exceed the reserve cap")]
+    ReserveCapExceeded,
+    #[msg("Reserve can't cover the swap")]
+    InsufficientReserve,
+    #[msg("Swap math overflowed")]
+    MathOverflow,
+}
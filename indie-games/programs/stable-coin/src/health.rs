@@ -0,0 +1,105 @@
+// Position health math, kept free of account types so it can be unit tested on its own.
+// Prices are DSC base units per whole collateral token and ratios are basis points.
+
+use crate::state::config_state::MAX_BPS;
+
+// DSC base units `collateral` base units are worth at `price`
+pub fn collateral_value(collateral: u64, price: u64, collateral_decimals: u8) -> Option<u64> {
+    let value = (collateral as u128).checked_mul(price as u128)?
+        / 10u128.checked_pow(collateral_decimals as u32)?;
+    u64::try_from(value).ok()
+}
+
+// collateral value over what `debt` needs at `liquidation_ratio_bps`, in basis points;
+// anything below `MAX_BPS` is liquidatable and a debt-free position is always healthy
+pub fn health_factor_bps(value: u64, debt: u64, liquidation_ratio_bps: u64) -> Option<u64> {
+    if debt == 0 {
+        return Some(u64::MAX);
+    }
+    let required = (debt as u128).checked_mul(liquidation_ratio_bps as u128)?;
+    if required == 0 {
+        return Some(u64::MAX);
+    }
+    let health = (value as u128)
+        .checked_mul(MAX_BPS as u128)?
+        .checked_mul(MAX_BPS as u128)?
+        / required;
+    Some(u64::try_from(health).unwrap_or(u64::MAX))
+}
+
+pub fn is_healthy(value: u64, debt: u64, liquidation_ratio_bps: u64) -> Option<bool> {
+    Some(health_factor_bps(value, debt, liquidation_ratio_bps)? >= MAX_BPS)
+}
+
+// debt a single liquidation may repay, dust positions can always be closed in full
+pub fn max_liquidatable_debt(debt: u64, close_factor_bps: u64) -> Option<u64> {
+    let share = (debt as u128).checked_mul(close_factor_bps as u128)? / MAX_BPS as u128;
+    Some(u64::try_from(share).ok()?.max(1).min(debt))
+}
+
+// collateral base units worth `repaid` DSC plus the liquidation bonus, rounded down
+pub fn seized_collateral(
+    repaid: u64,
+    price: u64,
+    collateral_decimals: u8,
+    bonus_bps: u64,
+) -> Option<u64> {
+    if price == 0 {
+        return None;
+    }
+    let seized = (repaid as u128)
+        .checked_mul(MAX_BPS.checked_add(bonus_bps)? as u128)?
+        .checked_mul(10u128.checked_pow(collateral_decimals as u32)?)?
+        / (MAX_BPS as u128)
+        / price as u128;
+    u64::try_from(seized).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+    // 150 DSC per SOL
+    const PRICE: u64 = 150_000_000;
+
+    #[test]
+    fn values_collateral_in_dsc_base_units() {
+        assert_eq!(collateral_value(SOL, PRICE, 9), Some(PRICE));
+        assert_eq!(collateral_value(SOL / 2, PRICE, 9), Some(PRICE / 2));
+        assert_eq!(collateral_value(u64::MAX, u64::MAX, 0), None);
+    }
+
+    #[test]
+    fn health_factor_crosses_one_at_the_liquidation_ratio() {
+        // 150 DSC of collateral at 150% backs exactly 100 DSC of debt
+        assert_eq!(health_factor_bps(PRICE, 100_000_000, 15_000), Some(MAX_BPS));
+        assert_eq!(is_healthy(PRICE, 100_000_000, 15_000), Some(true));
+        assert_eq!(is_healthy(PRICE, 100_000_001, 15_000), Some(false));
+        assert_eq!(health_factor_bps(PRICE, 200_000_000, 15_000), Some(5_000));
+    }
+
+    #[test]
+    fn debt_free_positions_are_always_healthy() {
+        assert_eq!(health_factor_bps(0, 0, 15_000), Some(u64::MAX));
+        assert_eq!(is_healthy(0, 0, 15_000), Some(true));
+    }
+
+    #[test]
+    fn close_factor_caps_a_single_liquidation() {
+        assert_eq!(max_liquidatable_debt(100, 5_000), Some(50));
+        assert_eq!(max_liquidatable_debt(100, MAX_BPS), Some(100));
+        assert_eq!(max_liquidatable_debt(1, 5_000), Some(1));
+        assert_eq!(max_liquidatable_debt(0, 5_000), Some(0));
+    }
+
+    #[test]
+    fn seized_collateral_includes_the_bonus() {
+        assert_eq!(seized_collateral(PRICE, PRICE, 9, 0), Some(SOL));
+        assert_eq!(
+            seized_collateral(PRICE, PRICE, 9, 1_000),
+            Some(SOL + SOL / 10)
+        );
+        assert_eq!(seized_collateral(PRICE, 0, 9, 1_000), None);
+    }
+}
@@ -0,0 +1,259 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer as sol_transfer, Transfer as SolTransfer},
+};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    errors::cdp_errors::*,
+    program::StableCoin,
+    rate::WAD,
+    state::{config_state::*, oracle_state::*},
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct ConfigArgs {
+    pub close_factor_bps: u64,
+    pub global_debt_ceiling: u64,
+}
+
+fn apply_config(config: &mut StablecoinConfig, args: ConfigArgs) -> Result<()> {
+    require!(
+        args.close_factor_bps > 0 && args.close_factor_bps <= MAX_BPS,
+        CdpError::InvalidConfig
+    );
+    config.close_factor_bps = args.close_factor_bps;
+    config.global_debt_ceiling = args.global_debt_ceiling;
+    Ok(())
+}
+
+// only the program's upgrade authority can initialize, and becomes the admin. Collateral
+// types are added with `add_collateral_type`
+pub fn init_config_handler(ctx: Context<InitConfigContext>, args: ConfigArgs) -> Result<()> {
+    // seed the SOL vault with its rent-exempt minimum so withdrawals can empty the deposits
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let shortfall = rent_exempt.saturating_sub(ctx.accounts.sol_vault.lamports());
+    if shortfall > 0 {
+        let cpi_accounts = SolTransfer {
+            from: ctx.accounts.admin.to_account_info(),
+            to: ctx.accounts.sol_vault.to_account_info(),
+        };
+        sol_transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            shortfall,
+        )?;
+    }
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.total_debt = 0;
+    config.bump = ctx.bumps.config;
+    apply_config(config, args)
+}
+
+#[derive(Accounts)]
+pub struct InitConfigContext<'info> {
+    #[account(
+        init,
+        seeds = [b"config"],
+        bump,
+        space = 8 + StablecoinConfig::INIT_SPACE,
+        payer = admin
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ CdpError::NotAuthorized
+    )]
+    pub program: Program<'info, StableCoin>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ CdpError::NotAuthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_config_handler(ctx: Context<UpdateConfigContext>, args: ConfigArgs) -> Result<()> {
+    apply_config(&mut ctx.accounts.config, args)
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfigContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CdpError::NotAuthorized
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct CollateralParamsArgs {
+    pub liquidation_ratio_bps: u64,
+    pub liquidation_bonus_bps: u64,
+    pub debt_ceiling: u64,
+    pub enabled: bool,
+}
+
+fn apply_collateral_params(
+    collateral: &mut CollateralConfig,
+    args: CollateralParamsArgs,
+) -> Result<()> {
+    // the bonus has to fit inside the over-collateralization or liquidations drain the vault
+    require!(
+        args.liquidation_ratio_bps >= MAX_BPS
+            && MAX_BPS + args.liquidation_bonus_bps <= args.liquidation_ratio_bps,
+        CdpError::InvalidConfig
+    );
+    collateral.liquidation_ratio_bps = args.liquidation_ratio_bps;
+    collateral.liquidation_bonus_bps = args.liquidation_bonus_bps;
+    collateral.debt_ceiling = args.debt_ceiling;
+    collateral.enabled = args.enabled;
+    Ok(())
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct AddCollateralTypeArgs {
+    pub collateral_mint: Pubkey,
+    pub params: CollateralParamsArgs,
+}
+
+// SPL collateral also needs `initialize_collateral_vault` before it can take deposits
+pub fn add_collateral_type_handler(
+    ctx: Context<AddCollateralTypeContext>,
+    args: AddCollateralTypeArgs,
+) -> Result<()> {
+    let collateral_decimals = if args.collateral_mint == NATIVE_SOL_COLLATERAL {
+        SOL_DECIMALS
+    } else {
+        let Some(mint) = ctx.accounts.collateral_mint.as_ref() else {
+            return err!(CdpError::InvalidCollateralAccount);
+        };
+        require_keys_eq!(
+            mint.key(),
+            args.collateral_mint,
+            CdpError::InvalidCollateralAccount
+        );
+        mint.decimals
+    };
+    let collateral = &mut ctx.accounts.collateral_config;
+    collateral.collateral_mint = args.collateral_mint;
+    collateral.collateral_decimals = collateral_decimals;
+    collateral.price_feed = ctx.accounts.price_feed.key();
+    collateral.total_normalized_debt = 0;
+    collateral.stability_fee_rate = WAD;
+    collateral.rate_index = WAD;
+    collateral.last_accrual = Clock::get()?.unix_timestamp;
+    collateral.bump = ctx.bumps.collateral_config;
+    apply_collateral_params(collateral, args.params)
+}
+
+#[derive(Accounts)]
+#[instruction(args: AddCollateralTypeArgs)]
+pub struct AddCollateralTypeContext<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CdpError::NotAuthorized
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        init,
+        seeds = [b"collateral", args.collateral_mint.as_ref()],
+        bump,
+        space = 8 + CollateralConfig::INIT_SPACE,
+        payer = admin
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    // only read for SPL collateral
+    pub collateral_mint: Option<Account<'info, Mint>>,
+    #[account(
+        constraint = price_feed.subject == args.collateral_mint @ CdpError::InvalidConfig,
+        constraint = price_feed.authority == config.admin @ CdpError::InvalidConfig
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// passing a price feed repoints the type at it
+pub fn update_collateral_type_handler(
+    ctx: Context<UpdateCollateralTypeContext>,
+    args: CollateralParamsArgs,
+) -> Result<()> {
+    let collateral = &mut ctx.accounts.collateral_config;
+    if let Some(price_feed) = ctx.accounts.price_feed.as_ref() {
+        require_keys_eq!(
+            price_feed.subject,
+            collateral.collateral_mint,
+            CdpError::InvalidConfig
+        );
+        require_keys_eq!(
+            price_feed.authority,
+            ctx.accounts.config.admin,
+            CdpError::InvalidConfig
+        );
+        collateral.price_feed = price_feed.key();
+    }
+    apply_collateral_params(collateral, args)
+}
+
+#[derive(Accounts)]
+pub struct UpdateCollateralTypeContext<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CdpError::NotAuthorized
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"collateral", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+    pub admin: Signer<'info>,
+}
+
+pub fn init_collateral_vault_handler(_ctx: Context<InitCollateralVaultContext>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitCollateralVaultContext<'info> {
+    #[account(
+        seeds = [b"collateral", collateral_mint.key().as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        init,
+        seeds = [b"collateral_vault", collateral_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        token::mint = collateral_mint,
+        token::authority = vault_authority
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    pub collateral_mint: Account<'info, Mint>,
+    /// CHECK: PDA, auth over all collateral vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
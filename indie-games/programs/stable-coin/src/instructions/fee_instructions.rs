@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+
+use crate::{
+    errors::cdp_errors::*,
+    rate::{accrued_fee, accrued_index, MAX_RATE_PER_SECOND, WAD},
+    state::config_state::*,
+};
+
+// rolls the type's rate index forward to now and mints the fee it added onto outstanding
+// debt into the surplus account, every instruction that reads or moves debt calls this first
+pub(crate) fn accrue_stability_fee<'info>(
+    config: &mut StablecoinConfig,
+    collateral: &mut CollateralConfig,
+    mint: &Account<'info, Mint>,
+    mint_bump: u8,
+    surplus: &Account<'info, TokenAccount>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let elapsed = now.saturating_sub(collateral.last_accrual);
+    if elapsed <= 0 {
+        return Ok(());
+    }
+    let new_index = accrued_index(
+        collateral.rate_index,
+        collateral.stability_fee_rate,
+        elapsed as u64,
+    )
+    .ok_or(CdpError::MathOverflow)?;
+    let fee = accrued_fee(
+        collateral.total_normalized_debt,
+        collateral.rate_index,
+        new_index,
+    )
+    .ok_or(CdpError::MathOverflow)?;
+    collateral.rate_index = new_index;
+    collateral.last_accrual = now;
+    if fee == 0 {
+        return Ok(());
+    }
+    config.total_debt = config
+        .total_debt
+        .checked_add(fee)
+        .ok_or(CdpError::MathOverflow)?;
+    let seeds: &[&[&[u8]]] = &[&[b"mint", &[mint_bump]]];
+    let cpi_accounts = MintTo {
+        mint: mint.to_account_info(),
+        to: surplus.to_account_info(),
+        authority: mint.to_account_info(),
+    };
+    mint_to(
+        CpiContext::new_with_signer(token_program, cpi_accounts, seeds),
+        fee,
+    )
+}
+
+pub fn init_surplus_handler(_ctx: Context<InitSurplusContext>) -> Result<()> {
+    Ok(())
+}
+
+// collects stability fees, owned by the config so only admin instructions can spend it
+#[derive(Accounts)]
+pub struct InitSurplusContext<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        init,
+        seeds = [b"surplus"],
+        bump,
+        payer = payer,
+        token::mint = mint,
+        token::authority = config
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"mint"],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+// settles everything the type owes at the old rate before the new one applies
+pub fn set_stability_fee_handler(ctx: Context<SetStabilityFeeContext>, rate: u128) -> Result<()> {
+    require!(
+        (WAD..=MAX_RATE_PER_SECOND).contains(&rate),
+        CdpError::InvalidConfig
+    );
+    let collateral = &mut ctx.accounts.collateral_config;
+    accrue_stability_fee(
+        &mut ctx.accounts.config,
+        collateral,
+        &ctx.accounts.mint,
+        ctx.bumps.mint,
+        &ctx.accounts.surplus,
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+    collateral.stability_fee_rate = rate;
+    msg!(
+        "stability fee of {} set to {} per second",
+        collateral.collateral_mint,
+        rate
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetStabilityFeeContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CdpError::NotAuthorized
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"collateral", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"surplus"],
+        bump
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
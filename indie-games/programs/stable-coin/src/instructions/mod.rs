@@ -0,0 +1,6 @@
+pub mod config_instructions;
+pub mod fee_instructions;
+pub mod position_instructions;
+pub mod price_feed_instructions;
+pub mod psm_instructions;
+pub mod token_instructions;
@@ -0,0 +1,685 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer as sol_transfer, Transfer as SolTransfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{burn, mint_to, transfer, Burn, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    errors::cdp_errors::*,
+    health::{max_liquidatable_debt, seized_collateral},
+    instructions::{
+        fee_instructions::accrue_stability_fee, price_feed_instructions::fresh_collateral_price,
+    },
+    rate::{normalize_down, normalize_up},
+    state::{config_state::*, oracle_state::*, position_state::*},
+};
+
+// pays collateral out of whichever vault backs the collateral type
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn release_collateral<'info>(
+    collateral: &CollateralConfig,
+    amount: u64,
+    recipient: AccountInfo<'info>,
+    sol_vault: Option<(&SystemAccount<'info>, u8)>,
+    collateral_vault: Option<&Account<'info, TokenAccount>>,
+    recipient_collateral_ata: Option<&Account<'info, TokenAccount>>,
+    vault_authority: (AccountInfo<'info>, u8),
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    if collateral.is_sol_backed() {
+        let Some((sol_vault, bump)) = sol_vault else {
+            return err!(CdpError::InvalidCollateralAccount);
+        };
+        let seeds: &[&[&[u8]]] = &[&[b"sol_vault", &[bump]]];
+        let cpi_accounts = SolTransfer {
+            from: sol_vault.to_account_info(),
+            to: recipient,
+        };
+        sol_transfer(
+            CpiContext::new_with_signer(system_program, cpi_accounts, seeds),
+            amount,
+        )
+    } else {
+        let (Some(collateral_vault), Some(recipient_collateral_ata)) =
+            (collateral_vault, recipient_collateral_ata)
+        else {
+            return err!(CdpError::InvalidCollateralAccount);
+        };
+        let (vault_authority, bump) = vault_authority;
+        let seeds: &[&[&[u8]]] = &[&[b"vault_authority", &[bump]]];
+        let cpi_accounts = Transfer {
+            from: collateral_vault.to_account_info(),
+            to: recipient_collateral_ata.to_account_info(),
+            authority: vault_authority,
+        };
+        transfer(
+            CpiContext::new_with_signer(token_program, cpi_accounts, seeds),
+            amount,
+        )
+    }
+}
+
+// books new borrowing, keeping the type and the whole system under their debt ceilings
+fn add_debt(
+    config: &mut StablecoinConfig,
+    collateral: &mut CollateralConfig,
+    position: &mut Position,
+    amount: u64,
+) -> Result<()> {
+    require!(collateral.enabled, CdpError::CollateralDisabled);
+    let normalized = normalize_up(amount, collateral.rate_index).ok_or(CdpError::MathOverflow)?;
+    position.normalized_debt = position
+        .normalized_debt
+        .checked_add(normalized)
+        .ok_or(CdpError::MathOverflow)?;
+    collateral.total_normalized_debt = collateral
+        .total_normalized_debt
+        .checked_add(normalized)
+        .ok_or(CdpError::MathOverflow)?;
+    require!(
+        collateral.total_debt().ok_or(CdpError::MathOverflow)? <= collateral.debt_ceiling,
+        CdpError::DebtCeilingExceeded
+    );
+    config.total_debt = config
+        .total_debt
+        .checked_add(amount)
+        .ok_or(CdpError::MathOverflow)?;
+    require!(
+        config.total_debt <= config.global_debt_ceiling,
+        CdpError::GlobalDebtCeilingExceeded
+    );
+    Ok(())
+}
+
+// repaying the full debt clears the position exactly, whatever the rounding
+fn reduce_debt(
+    config: &mut StablecoinConfig,
+    collateral: &mut CollateralConfig,
+    position: &mut Position,
+    amount: u64,
+) -> Result<()> {
+    let debt = position
+        .debt(collateral.rate_index)
+        .ok_or(CdpError::MathOverflow)?;
+    require!(amount <= debt, CdpError::DebtUnderflow);
+    let normalized = if amount == debt {
+        position.normalized_debt
+    } else {
+        normalize_down(amount, collateral.rate_index).ok_or(CdpError::MathOverflow)?
+    };
+    position.normalized_debt -= normalized;
+    collateral.total_normalized_debt = collateral.total_normalized_debt.saturating_sub(normalized);
+    config.total_debt = config.total_debt.saturating_sub(amount);
+    Ok(())
+}
+
+pub fn open_position_handler(ctx: Context<OpenPositionContext>) -> Result<()> {
+    let position = &mut ctx.accounts.position;
+    position.owner = ctx.accounts.owner.key();
+    position.collateral_mint = ctx.accounts.collateral_config.collateral_mint;
+    position.collateral = 0;
+    position.normalized_debt = 0;
+    position.bump = ctx.bumps.position;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenPositionContext<'info> {
+    #[account(
+        seeds = [b"collateral", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        init,
+        seeds = [
+            b"position",
+            owner.key().as_ref(),
+            collateral_config.collateral_mint.as_ref()
+        ],
+        bump,
+        space = 8 + Position::INIT_SPACE,
+        payer = owner
+    )]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn deposit_collateral_handler(
+    ctx: Context<DepositCollateralContext>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, CdpError::InvalidAmount);
+    let collateral = &ctx.accounts.collateral_config;
+    require!(collateral.enabled, CdpError::CollateralDisabled);
+    if collateral.is_sol_backed() {
+        let Some(sol_vault) = ctx.accounts.sol_vault.as_ref() else {
+            return err!(CdpError::InvalidCollateralAccount);
+        };
+        let cpi_accounts = SolTransfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: sol_vault.to_account_info(),
+        };
+        sol_transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+    } else {
+        let (Some(collateral_vault), Some(owner_collateral_ata)) = (
+            ctx.accounts.collateral_vault.as_ref(),
+            ctx.accounts.owner_collateral_ata.as_ref(),
+        ) else {
+            return err!(CdpError::InvalidCollateralAccount);
+        };
+        let cpi_accounts = Transfer {
+            from: owner_collateral_ata.to_account_info(),
+            to: collateral_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+    }
+    let position = &mut ctx.accounts.position;
+    position.collateral = position
+        .collateral
+        .checked_add(amount)
+        .ok_or(CdpError::MathOverflow)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositCollateralContext<'info> {
+    #[account(
+        seeds = [b"collateral", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            owner.key().as_ref(),
+            collateral_config.collateral_mint.as_ref()
+        ],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    // SOL collateral only
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+    // SPL collateral only
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", collateral_config.collateral_mint.as_ref()],
+        bump
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = collateral_config.collateral_mint,
+        token::authority = owner
+    )]
+    pub owner_collateral_ata: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+// mints DSC against the position as long as it stays above the collateral ratio
+pub fn mint_tokens_handler(ctx: Context<MintTokens>, quantity: u64) -> Result<()> {
+    require!(quantity > 0, CdpError::InvalidAmount);
+    let price = fresh_collateral_price(&ctx.accounts.price_feed)?;
+    let config = &mut ctx.accounts.config;
+    let collateral = &mut ctx.accounts.collateral_config;
+    accrue_stability_fee(
+        config,
+        collateral,
+        &ctx.accounts.mint,
+        ctx.bumps.mint,
+        &ctx.accounts.surplus,
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+    let position = &mut ctx.accounts.position;
+    add_debt(config, collateral, position, quantity)?;
+    require!(
+        position
+            .is_collateralized(
+                price,
+                collateral.collateral_decimals,
+                collateral.liquidation_ratio_bps,
+                collateral.rate_index
+            )
+            .ok_or(CdpError::MathOverflow)?,
+        CdpError::Undercollateralized
+    );
+
+    let seeds = &["mint".as_bytes(), &[ctx.bumps.mint]];
+    let signer = [&seeds[..]];
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                authority: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+            &signer,
+        ),
+        quantity,
+    )
+}
+
+#[derive(Accounts)]
+pub struct MintTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"collateral", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            owner.key().as_ref(),
+            collateral_config.collateral_mint.as_ref()
+        ],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(
+        address = collateral_config.price_feed
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump,
+        mint::authority = mint,
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"surplus"],
+        bump
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn repay_handler(ctx: Context<RepayContext>, amount: u64) -> Result<()> {
+    require!(amount > 0, CdpError::InvalidAmount);
+    let config = &mut ctx.accounts.config;
+    let collateral = &mut ctx.accounts.collateral_config;
+    accrue_stability_fee(
+        config,
+        collateral,
+        &ctx.accounts.mint,
+        ctx.bumps.mint,
+        &ctx.accounts.surplus,
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+    reduce_debt(config, collateral, &mut ctx.accounts.position, amount)?;
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.owner_dsc_ata.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    burn(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct RepayContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"collateral", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            owner.key().as_ref(),
+            collateral_config.collateral_mint.as_ref()
+        ],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"surplus"],
+        bump
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner
+    )]
+    pub owner_dsc_ata: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// debt-free positions can always withdraw, otherwise the remainder must stay collateralized
+pub fn withdraw_collateral_handler(
+    ctx: Context<WithdrawCollateralContext>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, CdpError::InvalidAmount);
+    let config = &mut ctx.accounts.config;
+    let collateral = &mut ctx.accounts.collateral_config;
+    accrue_stability_fee(
+        config,
+        collateral,
+        &ctx.accounts.mint,
+        ctx.bumps.mint,
+        &ctx.accounts.surplus,
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+    let position = &mut ctx.accounts.position;
+    position.collateral = position
+        .collateral
+        .checked_sub(amount)
+        .ok_or(CdpError::InsufficientCollateral)?;
+    if position.normalized_debt > 0 {
+        let price = fresh_collateral_price(&ctx.accounts.price_feed)?;
+        require!(
+            position
+                .is_collateralized(
+                    price,
+                    collateral.collateral_decimals,
+                    collateral.liquidation_ratio_bps,
+                    collateral.rate_index
+                )
+                .ok_or(CdpError::MathOverflow)?,
+            CdpError::Undercollateralized
+        );
+    }
+    release_collateral(
+        collateral,
+        amount,
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.sol_vault.as_ref().zip(ctx.bumps.sol_vault),
+        ctx.accounts.collateral_vault.as_ref(),
+        ctx.accounts.owner_collateral_ata.as_ref(),
+        (
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+        ),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCollateralContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"collateral", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            owner.key().as_ref(),
+            collateral_config.collateral_mint.as_ref()
+        ],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(
+        address = collateral_config.price_feed
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"surplus"],
+        bump
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", collateral_config.collateral_mint.as_ref()],
+        bump
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = collateral_config.collateral_mint,
+        token::authority = owner
+    )]
+    pub owner_collateral_ata: Option<Account<'info, TokenAccount>>,
+    /// CHECK: PDA, auth over all collateral vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+// anyone may repay part of an unhealthy position's debt, capped by the close factor,
+// and take the matching collateral plus the liquidation bonus
+pub fn liquidate_handler(ctx: Context<LiquidateContext>, repay_amount: u64) -> Result<()> {
+    require!(repay_amount > 0, CdpError::InvalidAmount);
+    let price = fresh_collateral_price(&ctx.accounts.price_feed)?;
+    let config = &mut ctx.accounts.config;
+    let collateral = &mut ctx.accounts.collateral_config;
+    accrue_stability_fee(
+        config,
+        collateral,
+        &ctx.accounts.mint,
+        ctx.bumps.mint,
+        &ctx.accounts.surplus,
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+    let position = &mut ctx.accounts.position;
+    require!(
+        !position
+            .is_collateralized(
+                price,
+                collateral.collateral_decimals,
+                collateral.liquidation_ratio_bps,
+                collateral.rate_index
+            )
+            .ok_or(CdpError::MathOverflow)?,
+        CdpError::PositionHealthy
+    );
+    let debt = position
+        .debt(collateral.rate_index)
+        .ok_or(CdpError::MathOverflow)?;
+    require!(
+        repay_amount
+            <= max_liquidatable_debt(debt, config.close_factor_bps)
+                .ok_or(CdpError::MathOverflow)?,
+        CdpError::ExceedsCloseFactor
+    );
+    let seized = seized_collateral(
+        repay_amount,
+        price,
+        collateral.collateral_decimals,
+        collateral.liquidation_bonus_bps,
+    )
+    .ok_or(CdpError::MathOverflow)?
+    .min(position.collateral);
+    reduce_debt(config, collateral, position, repay_amount)?;
+    position.collateral -= seized;
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.liquidator_dsc_ata.to_account_info(),
+        authority: ctx.accounts.liquidator.to_account_info(),
+    };
+    burn(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        repay_amount,
+    )?;
+    release_collateral(
+        collateral,
+        seized,
+        ctx.accounts.liquidator.to_account_info(),
+        ctx.accounts.sol_vault.as_ref().zip(ctx.bumps.sol_vault),
+        ctx.accounts.collateral_vault.as_ref(),
+        ctx.accounts.liquidator_collateral_ata.as_ref(),
+        (
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+        ),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+    msg!(
+        "position of {} liquidated: {} DSC repaid for {} collateral",
+        position.owner,
+        repay_amount,
+        seized
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LiquidateContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"collateral", position.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            position.owner.as_ref(),
+            position.collateral_mint.as_ref()
+        ],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+    #[account(
+        address = collateral_config.price_feed
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"surplus"],
+        bump
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = liquidator
+    )]
+    pub liquidator_dsc_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"sol_vault"],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", collateral_config.collateral_mint.as_ref()],
+        bump
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = collateral_config.collateral_mint,
+        token::authority = liquidator
+    )]
+    pub liquidator_collateral_ata: Option<Account<'info, TokenAccount>>,
+    /// CHECK: PDA, auth over all collateral vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub liquidator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
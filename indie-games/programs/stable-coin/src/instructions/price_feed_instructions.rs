@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::oracle_errors::*, state::oracle_state::*};
+
+// the collateral price in DSC base units, failing once the feed has gone stale
+pub(crate) fn fresh_collateral_price(price_feed: &PriceFeed) -> Result<u64> {
+    require!(
+        price_feed.is_fresh(Clock::get()?.unix_timestamp),
+        OracleError::StalePrice
+    );
+    Ok(price_feed.collateral_price())
+}
+
+fn apply_price_feed_config(
+    price_feed: &mut PriceFeed,
+    updaters: Vec<Pubkey>,
+    max_staleness: i64,
+) -> Result<()> {
+    require!(
+        updaters.len() <= MAX_PRICE_UPDATERS && max_staleness > 0,
+        OracleError::InvalidFeedConfig
+    );
+    price_feed.updaters = updaters;
+    price_feed.max_staleness = max_staleness;
+    Ok(())
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct InitPriceFeedArgs {
+    pub subject: Pubkey,
+    pub updaters: Vec<Pubkey>,
+    pub max_staleness: i64,
+}
+
+// anyone can run a feed, consumers decide whose feeds they trust: collateral types only take
+// the config admin's, game assets only their game owner's
+pub fn init_price_feed_handler(
+    ctx: Context<InitPriceFeedContext>,
+    args: InitPriceFeedArgs,
+) -> Result<()> {
+    let price_feed = &mut ctx.accounts.price_feed;
+    price_feed.subject = args.subject;
+    price_feed.authority = ctx.accounts.authority.key();
+    // not fresh until an updater pushes the first price
+    price_feed.price = 0;
+    price_feed.confidence = 0;
+    price_feed.last_updated = 0;
+    price_feed.bump = ctx.bumps.price_feed;
+    apply_price_feed_config(price_feed, args.updaters, args.max_staleness)
+}
+
+#[derive(Accounts)]
+#[instruction(args: InitPriceFeedArgs)]
+pub struct InitPriceFeedContext<'info> {
+    #[account(
+        init,
+        seeds = [b"price_feed", args.subject.as_ref(), authority.key().as_ref()],
+        bump,
+        space = 8 + PriceFeed::INIT_SPACE,
+        payer = authority
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct PriceFeedConfigArgs {
+    pub updaters: Vec<Pubkey>,
+    pub max_staleness: i64,
+}
+
+pub fn update_price_feed_config_handler(
+    ctx: Context<UpdatePriceFeedConfigContext>,
+    args: PriceFeedConfigArgs,
+) -> Result<()> {
+    apply_price_feed_config(
+        &mut ctx.accounts.price_feed,
+        args.updaters,
+        args.max_staleness,
+    )
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceFeedConfigContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"price_feed", price_feed.subject.as_ref(), authority.key().as_ref()],
+        bump = price_feed.bump,
+        has_one = authority @ OracleError::NotAuthorized
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct PushPriceArgs {
+    pub price: u64,
+    pub confidence: u64,
+}
+
+pub fn push_price_handler(ctx: Context<PushPriceContext>, args: PushPriceArgs) -> Result<()> {
+    let price_feed = &mut ctx.accounts.price_feed;
+    require!(
+        price_feed.can_update(&ctx.accounts.updater.key()),
+        OracleError::NotAuthorized
+    );
+    require!(args.price > 0, OracleError::InvalidPrice);
+    price_feed.price = args.price;
+    price_feed.confidence = args.confidence;
+    price_feed.last_updated = Clock::get()?.unix_timestamp;
+    msg!(
+        "price of {} set to {} +/- {}",
+        price_feed.subject,
+        args.price,
+        args.confidence
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PushPriceContext<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"price_feed",
+            price_feed.subject.as_ref(),
+            price_feed.authority.as_ref()
+        ],
+        bump = price_feed.bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    pub updater: Signer<'info>,
+}
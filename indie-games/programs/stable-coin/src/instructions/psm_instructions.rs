@@ -0,0 +1,295 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{burn, mint_to, transfer, Burn, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    errors::{cdp_errors::CdpError, psm_errors::*},
+    state::{config_state::*, psm_state::*},
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct PsmParamsArgs {
+    pub fee_bps: u64,
+    pub cap: u64,
+}
+
+fn apply_psm_params(reserve: &mut PsmReserve, args: PsmParamsArgs) -> Result<()> {
+    require!(args.fee_bps < MAX_BPS, PsmError::InvalidReserveConfig);
+    reserve.fee_bps = args.fee_bps;
+    reserve.cap = args.cap;
+    Ok(())
+}
+
+pub fn init_psm_reserve_handler(
+    ctx: Context<InitPsmReserveContext>,
+    args: PsmParamsArgs,
+) -> Result<()> {
+    require!(
+        ctx.accounts.stable_mint.decimals == DSC_DECIMALS,
+        PsmError::InvalidStableMint
+    );
+    let reserve = &mut ctx.accounts.psm_reserve;
+    reserve.stable_mint = ctx.accounts.stable_mint.key();
+    reserve.reserve = 0;
+    reserve.bump = ctx.bumps.psm_reserve;
+    apply_psm_params(reserve, args)
+}
+
+#[derive(Accounts)]
+pub struct InitPsmReserveContext<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CdpError::NotAuthorized
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        init,
+        seeds = [b"psm", stable_mint.key().as_ref()],
+        bump,
+        space = 8 + PsmReserve::INIT_SPACE,
+        payer = admin
+    )]
+    pub psm_reserve: Account<'info, PsmReserve>,
+    #[account(
+        init,
+        seeds = [b"psm_vault", stable_mint.key().as_ref()],
+        bump,
+        payer = admin,
+        token::mint = stable_mint,
+        token::authority = vault_authority
+    )]
+    pub psm_vault: Account<'info, TokenAccount>,
+    pub stable_mint: Account<'info, Mint>,
+    /// CHECK: PDA, auth over all collateral vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+// lowering the cap below the current reserve only stops new swaps in
+pub fn update_psm_reserve_handler(
+    ctx: Context<UpdatePsmReserveContext>,
+    args: PsmParamsArgs,
+) -> Result<()> {
+    apply_psm_params(&mut ctx.accounts.psm_reserve, args)
+}
+
+#[derive(Accounts)]
+pub struct UpdatePsmReserveContext<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CdpError::NotAuthorized
+    )]
+    pub config: Account<'info, StablecoinConfig>,
+    #[account(
+        mut,
+        seeds = [b"psm", psm_reserve.stable_mint.as_ref()],
+        bump = psm_reserve.bump
+    )]
+    pub psm_reserve: Account<'info, PsmReserve>,
+    pub admin: Signer<'info>,
+}
+
+// takes `amount` of the stable into the reserve and mints the same in DSC, the fee share
+// of it to the surplus account
+pub fn swap_stable_for_dsc_handler(
+    ctx: Context<SwapStableForDscContext>,
+    amount: u64,
+) -> Result<()> {
+    let reserve = &mut ctx.accounts.psm_reserve;
+    let fee = reserve.fee(amount).ok_or(PsmError::MathOverflow)?;
+    require!(amount > fee, PsmError::InvalidAmount);
+    reserve.reserve = reserve
+        .reserve
+        .checked_add(amount)
+        .ok_or(PsmError::MathOverflow)?;
+    require!(reserve.reserve <= reserve.cap, PsmError::ReserveCapExceeded);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_stable_ata.to_account_info(),
+        to: ctx.accounts.psm_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        amount,
+    )?;
+    let seeds: &[&[&[u8]]] = &[&[b"mint", &[ctx.bumps.mint]]];
+    for (to, quantity) in [
+        (ctx.accounts.user_dsc_ata.to_account_info(), amount - fee),
+        (ctx.accounts.surplus.to_account_info(), fee),
+    ] {
+        if quantity == 0 {
+            continue;
+        }
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to,
+            authority: ctx.accounts.mint.to_account_info(),
+        };
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                seeds,
+            ),
+            quantity,
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SwapStableForDscContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"psm", psm_reserve.stable_mint.as_ref()],
+        bump = psm_reserve.bump
+    )]
+    pub psm_reserve: Account<'info, PsmReserve>,
+    #[account(
+        mut,
+        seeds = [b"psm_vault", psm_reserve.stable_mint.as_ref()],
+        bump
+    )]
+    pub psm_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = psm_reserve.stable_mint,
+        token::authority = user
+    )]
+    pub user_stable_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+    )]
+    pub user_dsc_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"surplus"],
+        bump
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// takes `amount` DSC, keeps the fee in the surplus account, burns the rest and pays it
+// out of the reserve in the stable
+pub fn swap_dsc_for_stable_handler(
+    ctx: Context<SwapDscForStableContext>,
+    amount: u64,
+) -> Result<()> {
+    let reserve = &mut ctx.accounts.psm_reserve;
+    let fee = reserve.fee(amount).ok_or(PsmError::MathOverflow)?;
+    require!(amount > fee, PsmError::InvalidAmount);
+    let payout = amount - fee;
+    reserve.reserve = reserve
+        .reserve
+        .checked_sub(payout)
+        .ok_or(PsmError::InsufficientReserve)?;
+
+    if fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_dsc_ata.to_account_info(),
+            to: ctx.accounts.surplus.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            fee,
+        )?;
+    }
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.user_dsc_ata.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    burn(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        payout,
+    )?;
+    let seeds: &[&[&[u8]]] = &[&[b"vault_authority", &[ctx.bumps.vault_authority]]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.psm_vault.to_account_info(),
+        to: ctx.accounts.user_stable_ata.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        ),
+        payout,
+    )
+}
+
+#[derive(Accounts)]
+pub struct SwapDscForStableContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"psm", psm_reserve.stable_mint.as_ref()],
+        bump = psm_reserve.bump
+    )]
+    pub psm_reserve: Account<'info, PsmReserve>,
+    #[account(
+        mut,
+        seeds = [b"psm_vault", psm_reserve.stable_mint.as_ref()],
+        bump
+    )]
+    pub psm_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = psm_reserve.stable_mint,
+        token::authority = user
+    )]
+    pub user_stable_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user
+    )]
+    pub user_dsc_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"surplus"],
+        bump
+    )]
+    pub surplus: Account<'info, TokenAccount>,
+    /// CHECK: PDA, auth over all collateral vaults
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+use crate::state::config_state::DSC_DECIMALS;
+
+pub fn init_token_handler(_ctx: Context<InitToken>) -> Result<()> {
+    msg!("Token initiated successfully");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitToken<'info> {
+    #[account(
+        init,
+        seeds = [b"mint"],
+        bump,
+        payer = payer,
+        mint::decimals = DSC_DECIMALS,
+        mint::authority = mint,
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+
+pub mod errors;
+pub mod health;
+pub mod instructions;
+pub mod rate;
+pub mod state;
+
+use instructions::{
+    config_instructions::*, fee_instructions::*, position_instructions::*,
+    price_feed_instructions::*, psm_instructions::*, token_instructions::*,
 };
 
 declare_id!("9c4urtawvj6Q8FBPLCDQ5GGW3jAHJRwbnRqzFjLBZ8S5");
@@ -9,70 +16,105 @@ declare_id!("9c4urtawvj6Q8FBPLCDQ5GGW3jAHJRwbnRqzFjLBZ8S5");
 #[program]
 pub mod stable_coin {
     use super::*;
-    pub fn init_token(_ctx: Context<InitToken>) -> Result<()> {
-        msg!("Token initiated successfully");
-        Ok(())
+    pub fn init_token(ctx: Context<InitToken>) -> Result<()> {
+        init_token_handler(ctx)
+    }
+
+    pub fn initialize_config(ctx: Context<InitConfigContext>, args: ConfigArgs) -> Result<()> {
+        init_config_handler(ctx, args)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfigContext>, args: ConfigArgs) -> Result<()> {
+        update_config_handler(ctx, args)
+    }
+
+    pub fn add_collateral_type(
+        ctx: Context<AddCollateralTypeContext>,
+        args: AddCollateralTypeArgs,
+    ) -> Result<()> {
+        add_collateral_type_handler(ctx, args)
+    }
+
+    pub fn update_collateral_type(
+        ctx: Context<UpdateCollateralTypeContext>,
+        args: CollateralParamsArgs,
+    ) -> Result<()> {
+        update_collateral_type_handler(ctx, args)
+    }
+
+    pub fn initialize_surplus(ctx: Context<InitSurplusContext>) -> Result<()> {
+        init_surplus_handler(ctx)
+    }
+
+    pub fn set_stability_fee(ctx: Context<SetStabilityFeeContext>, rate: u128) -> Result<()> {
+        set_stability_fee_handler(ctx, rate)
+    }
+
+    pub fn initialize_collateral_vault(ctx: Context<InitCollateralVaultContext>) -> Result<()> {
+        init_collateral_vault_handler(ctx)
+    }
+
+    pub fn initialize_price_feed(
+        ctx: Context<InitPriceFeedContext>,
+        args: InitPriceFeedArgs,
+    ) -> Result<()> {
+        init_price_feed_handler(ctx, args)
+    }
+
+    pub fn update_price_feed_config(
+        ctx: Context<UpdatePriceFeedConfigContext>,
+        args: PriceFeedConfigArgs,
+    ) -> Result<()> {
+        update_price_feed_config_handler(ctx, args)
+    }
+
+    pub fn push_price(ctx: Context<PushPriceContext>, args: PushPriceArgs) -> Result<()> {
+        push_price_handler(ctx, args)
+    }
+
+    pub fn open_position(ctx: Context<OpenPositionContext>) -> Result<()> {
+        open_position_handler(ctx)
+    }
+
+    pub fn deposit_collateral(ctx: Context<DepositCollateralContext>, amount: u64) -> Result<()> {
+        deposit_collateral_handler(ctx, amount)
     }
 
     pub fn mint_tokens(ctx: Context<MintTokens>, quantity: u64) -> Result<()> {
-        let seeds = &["mint".as_bytes(), &[ctx.bumps.mint]];
-        let signer = [&seeds[..]];
-
-        mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    authority: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.destination.to_account_info(),
-                    mint: ctx.accounts.mint.to_account_info(),
-                },
-                &signer,
-            ),
-            quantity,
-        )?;
-
-        Ok(())
+        mint_tokens_handler(ctx, quantity)
     }
-}
 
-#[derive(Accounts)]
-pub struct InitToken<'info> {
-    #[account(
-        init,
-        seeds = [b"mint"],
-        bump,
-        payer = payer,
-        mint::decimals = 6,
-        mint::authority = mint,
-    )]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub rent: Sysvar<'info, Rent>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-}
+    pub fn repay(ctx: Context<RepayContext>, amount: u64) -> Result<()> {
+        repay_handler(ctx, amount)
+    }
+
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateralContext>, amount: u64) -> Result<()> {
+        withdraw_collateral_handler(ctx, amount)
+    }
+
+    pub fn liquidate(ctx: Context<LiquidateContext>, repay_amount: u64) -> Result<()> {
+        liquidate_handler(ctx, repay_amount)
+    }
+
+    pub fn initialize_psm_reserve(
+        ctx: Context<InitPsmReserveContext>,
+        args: PsmParamsArgs,
+    ) -> Result<()> {
+        init_psm_reserve_handler(ctx, args)
+    }
 
-#[derive(Accounts)]
-pub struct MintTokens<'info> {
-    #[account(
-        mut,
-        seeds = [b"mint"],
-        bump,
-        mint::authority = mint,
-    )]
-    pub mint: Account<'info, Mint>,
-    #[account(
-        init_if_needed,
-        payer = payer,
-        associated_token::mint = mint,
-        associated_token::authority = payer,
-    )]
-    pub destination: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub rent: Sysvar<'info, Rent>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub fn update_psm_reserve(
+        ctx: Context<UpdatePsmReserveContext>,
+        args: PsmParamsArgs,
+    ) -> Result<()> {
+        update_psm_reserve_handler(ctx, args)
+    }
+
+    pub fn swap_stable_for_dsc(ctx: Context<SwapStableForDscContext>, amount: u64) -> Result<()> {
+        swap_stable_for_dsc_handler(ctx, amount)
+    }
+
+    pub fn swap_dsc_for_stable(ctx: Context<SwapDscForStableContext>, amount: u64) -> Result<()> {
+        swap_dsc_for_stable_handler(ctx, amount)
+    }
 }
@@ -0,0 +1,101 @@
+// Stability fee math. Rates and the accumulated index are WAD fixed point, so a rate of
+// `WAD` charges nothing and the index starts at `WAD` when a collateral type opens.
+
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+// roughly 100% a year, anything steeper is a misconfiguration
+pub const MAX_RATE_PER_SECOND: u128 = WAD + 22_000_000_000;
+
+fn wad_mul(a: u128, b: u128) -> Option<u128> {
+    a.checked_mul(b).map(|product| product / WAD)
+}
+
+// `rate` compounded over `seconds`, by squaring
+pub fn rpow(mut rate: u128, mut seconds: u64) -> Option<u128> {
+    let mut result = WAD;
+    while seconds > 0 {
+        if seconds & 1 == 1 {
+            result = wad_mul(result, rate)?;
+        }
+        seconds >>= 1;
+        if seconds > 0 {
+            rate = wad_mul(rate, rate)?;
+        }
+    }
+    Some(result)
+}
+
+// the index after `elapsed` seconds at `rate_per_second`
+pub fn accrued_index(rate_index: u128, rate_per_second: u128, elapsed: u64) -> Option<u128> {
+    wad_mul(rate_index, rpow(rate_per_second, elapsed)?)
+}
+
+// DSC owed on `normalized_debt`, rounded up so the protocol never under-counts
+pub fn debt_amount(normalized_debt: u128, rate_index: u128) -> Option<u64> {
+    let debt = normalized_debt
+        .checked_mul(rate_index)?
+        .checked_add(WAD - 1)?
+        / WAD;
+    u64::try_from(debt).ok()
+}
+
+// normalized debt `amount` DSC of new borrowing adds, rounded up
+pub fn normalize_up(amount: u64, rate_index: u128) -> Option<u128> {
+    if rate_index == 0 {
+        return None;
+    }
+    let scaled = (amount as u128).checked_mul(WAD)?;
+    Some(scaled.checked_add(rate_index - 1)? / rate_index)
+}
+
+// normalized debt `amount` DSC of repayment clears, rounded down
+pub fn normalize_down(amount: u64, rate_index: u128) -> Option<u128> {
+    (amount as u128).checked_mul(WAD)?.checked_div(rate_index)
+}
+
+// fee the outstanding debt picked up while the index moved from `old_index` to `new_index`
+pub fn accrued_fee(total_normalized_debt: u128, old_index: u128, new_index: u128) -> Option<u64> {
+    let fee = total_normalized_debt.checked_mul(new_index.checked_sub(old_index)?)? / WAD;
+    u64::try_from(fee).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_fee_leaves_the_index_alone() {
+        assert_eq!(rpow(WAD, 365 * 24 * 3600), Some(WAD));
+        assert_eq!(accrued_index(WAD, WAD, 1_000), Some(WAD));
+    }
+
+    #[test]
+    fn rate_compounds_per_second() {
+        let rate = WAD + WAD / 100;
+        assert_eq!(rpow(rate, 0), Some(WAD));
+        assert_eq!(rpow(rate, 1), Some(rate));
+        // 1.01^2 and 1.01^3
+        assert_eq!(rpow(rate, 2), Some(1_020_100_000_000_000_000));
+        assert_eq!(rpow(rate, 3), Some(1_030_301_000_000_000_000));
+    }
+
+    #[test]
+    fn max_rate_over_a_year_stays_in_range() {
+        let year = rpow(MAX_RATE_PER_SECOND, 365 * 24 * 3600).unwrap();
+        assert!(year > WAD * 19 / 10 && year < WAD * 21 / 10);
+    }
+
+    #[test]
+    fn debt_rounds_against_the_borrower() {
+        let index = WAD + WAD / 2;
+        let normalized = normalize_up(10, index).unwrap();
+        assert!(debt_amount(normalized, index).unwrap() >= 10);
+        assert!(normalize_down(10, index).unwrap() <= normalized);
+        assert_eq!(debt_amount(0, index), Some(0));
+    }
+
+    #[test]
+    fn fee_tracks_index_growth() {
+        assert_eq!(accrued_fee(1_000, WAD, WAD + WAD / 10), Some(100));
+        assert_eq!(accrued_fee(1_000, WAD, WAD), Some(0));
+    }
+}
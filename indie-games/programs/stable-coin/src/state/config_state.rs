@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::rate::debt_amount;
+
+pub const MAX_BPS: u64 = 10_000;
+pub const DSC_DECIMALS: u8 = 6;
+// collateral mint of positions backed by lamports rather than an SPL token
+pub const NATIVE_SOL_COLLATERAL: Pubkey = anchor_lang::system_program::ID;
+pub const SOL_DECIMALS: u8 = 9;
+
+// seeded by `b"config"`, one per deployment
+#[account]
+#[derive(InitSpace)]
+pub struct StablecoinConfig {
+    pub admin: Pubkey,
+    // share of a position's debt one liquidation may repay
+    pub close_factor_bps: u64,
+    pub global_debt_ceiling: u64,
+    // DSC owed across every collateral type as of each type's last accrual
+    pub total_debt: u64,
+    pub bump: u8,
+}
+
+// seeded by `b"collateral"` and the collateral mint, one per accepted collateral type
+#[account]
+#[derive(InitSpace)]
+pub struct CollateralConfig {
+    // `NATIVE_SOL_COLLATERAL` for SOL backed positions
+    pub collateral_mint: Pubkey,
+    pub collateral_decimals: u8,
+    // minimum collateral value per unit of debt, 15_000 needs 1.5 DSC locked per DSC minted
+    pub liquidation_ratio_bps: u64,
+    // extra collateral a liquidator receives on top of the debt they repay
+    pub liquidation_bonus_bps: u64,
+    pub debt_ceiling: u64,
+    pub price_feed: Pubkey,
+    // disabled types take no new collateral or debt but can still be repaid and liquidated
+    pub enabled: bool,
+    // sum of this type's normalized debt, `rate_index` scales it to DSC
+    pub total_normalized_debt: u128,
+    // per-second WAD factor the index compounds by, `WAD` charges no fee
+    pub stability_fee_rate: u128,
+    pub rate_index: u128,
+    pub last_accrual: i64,
+    pub bump: u8,
+}
+
+impl CollateralConfig {
+    pub fn is_sol_backed(&self) -> bool {
+        self.collateral_mint == NATIVE_SOL_COLLATERAL
+    }
+
+    // DSC owed on this type at the current index
+    pub fn total_debt(&self) -> Option<u64> {
+        debt_amount(self.total_normalized_debt, self.rate_index)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod config_state;
+pub mod oracle_state;
+pub mod position_state;
+pub mod psm_state;
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_PRICE_UPDATERS: usize = 4;
+
+// seeded by `b"price_feed"`, the subject it prices and its authority. Shared with indie-games,
+// where the subject is a game asset account rather than a collateral mint
+#[account]
+#[derive(InitSpace)]
+pub struct PriceFeed {
+    pub subject: Pubkey,
+    // manages updaters and staleness, whoever created the feed
+    pub authority: Pubkey,
+    #[max_len(MAX_PRICE_UPDATERS)]
+    pub updaters: Vec<Pubkey>,
+    // DSC base units per whole token of the subject
+    pub price: u64,
+    // half-width of the band the updater is confident the price sits in
+    pub confidence: u64,
+    pub last_updated: i64,
+    pub max_staleness: i64,
+    pub bump: u8,
+}
+
+impl PriceFeed {
+    pub fn is_fresh(&self, now: i64) -> bool {
+        self.last_updated > 0 && now.saturating_sub(self.last_updated) <= self.max_staleness
+    }
+
+    pub fn can_update(&self, updater: &Pubkey) -> bool {
+        self.updaters.contains(updater)
+    }
+
+    // collateral is valued at the bottom of the band
+    pub fn collateral_price(&self) -> u64 {
+        self.price.saturating_sub(self.confidence)
+    }
+}
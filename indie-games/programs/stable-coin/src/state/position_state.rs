@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use super::config_state::MAX_BPS;
+use crate::{
+    health::{collateral_value, health_factor_bps},
+    rate::debt_amount,
+};
+
+// seeded by `b"position"`, the owner and the collateral mint
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    // collateral base units held in the vault for this position
+    pub collateral: u64,
+    // DSC minted against it divided by the rate index at the time, so fees accrue implicitly
+    pub normalized_debt: u128,
+    pub bump: u8,
+}
+
+impl Position {
+    // DSC base units owed at `rate_index`
+    pub fn debt(&self, rate_index: u128) -> Option<u64> {
+        debt_amount(self.normalized_debt, rate_index)
+    }
+
+    pub fn health_factor_bps(
+        &self,
+        price: u64,
+        collateral_decimals: u8,
+        collateral_ratio_bps: u64,
+        rate_index: u128,
+    ) -> Option<u64> {
+        let value = collateral_value(self.collateral, price, collateral_decimals)?;
+        health_factor_bps(value, self.debt(rate_index)?, collateral_ratio_bps)
+    }
+
+    pub fn is_collateralized(
+        &self,
+        price: u64,
+        collateral_decimals: u8,
+        collateral_ratio_bps: u64,
+        rate_index: u128,
+    ) -> Option<bool> {
+        Some(
+            self.health_factor_bps(price, collateral_decimals, collateral_ratio_bps, rate_index)?
+                >= MAX_BPS,
+        )
+    }
+}
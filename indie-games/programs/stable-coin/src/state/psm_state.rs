@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use super::config_state::MAX_BPS;
+
+// seeded by `b"psm"` and the stablecoin mint it swaps
+#[account]
+#[derive(InitSpace)]
+pub struct PsmReserve {
+    pub stable_mint: Pubkey,
+    // charged on both swap directions and paid to the surplus account in DSC
+    pub fee_bps: u64,
+    // most stable base units the reserve will hold
+    pub cap: u64,
+    // stable base units held, every one of them backs a DSC minted through the module
+    pub reserve: u64,
+    pub bump: u8,
+}
+
+impl PsmReserve {
+    // rounded up so dust swaps can't dodge the fee
+    pub fn fee(&self, amount: u64) -> Option<u64> {
+        let fee = (amount as u128)
+            .checked_mul(self.fee_bps as u128)?
+            .checked_add(MAX_BPS as u128 - 1)?
+            / MAX_BPS as u128;
+        u64::try_from(fee).ok()
+    }
+}
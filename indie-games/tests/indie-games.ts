@@ -6,18 +6,34 @@ import {
   SystemProgram,
   Keypair,
   SendTransactionError,
+  LAMPORTS_PER_SOL,
+  Transaction,
 } from "@solana/web3.js";
 import { BN } from "bn.js";
 import { expect } from "chai";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
   getAccount,
   getAssociatedTokenAddress,
   getOrCreateAssociatedTokenAccount,
+  mintTo,
+  createAccount,
+  transfer,
+  createTransferCheckedInstruction,
+  getMint,
+  getNonTransferable,
+  getPermanentDelegate,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
 import { IndieGames } from "../target/types/indie_games";
 import { StableCoin } from "../target/types/stable_coin";
+import { AssetTransferHook } from "../target/types/asset_transfer_hook";
+
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
 
 //key-pair : music unfair salute relief valve tent captain reveal knock snack hip shrimp
 
@@ -28,6 +44,8 @@ describe("Asset Minting Tests", () => {
     .IndieGames as Program<IndieGames>;
   const stable_coin_program = anchor.workspace
     .StableCoin as Program<StableCoin>;
+  const hook_program = anchor.workspace
+    .AssetTransferHook as Program<AssetTransferHook>;
   let asset_data_account: PublicKey;
   let asset_mint: PublicKey;
   let game_acc: PublicKey;
@@ -38,6 +56,19 @@ describe("Asset Minting Tests", () => {
   let signer = provider.wallet.publicKey;
   let asset_ata_auth: PublicKey;
   let asset_ata: PublicKey;
+  let wallet_mint_count: PublicKey;
+  let price_feed: PublicKey;
+  let dsc_config: PublicKey;
+  let dsc_position: PublicKey;
+  let sol_price_feed: PublicKey;
+  let sol_vault: PublicKey;
+  let dsc_surplus: PublicKey;
+  let sol_collateral_config: PublicKey;
+  let marketplace: PublicKey;
+  const fee_treasury = Keypair.generate();
+  let fee_treasury_ata: PublicKey;
+  const buyer = Keypair.generate();
+  let buyer_dsc_ata: PublicKey;
 
   let game = {
     name: "Game",
@@ -52,13 +83,31 @@ describe("Asset Minting Tests", () => {
     score: 10,
   };
 
-  const get_dsc = async () => {
+  // `anchor test` deploys with the provider wallet as upgrade authority, the
+  // only key allowed to initialize the global configs
+  const program_data = (program_id: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [program_id.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_ID
+    )[0];
+
+  // the SOL price has to be fresh for every mint against the position
+  const mint_dsc = async (amount: number) => {
+    await stable_coin_program.methods
+      .pushPrice({ price: new BN(150_000_000), confidence: new BN(0) })
+      .accountsStrict({ priceFeed: sol_price_feed, updater: signer })
+      .rpc();
     await stable_coin_program.methods
-      .mintTokens(new BN(10))
+      .mintTokens(new BN(amount))
       .accountsStrict({
+        config: dsc_config,
+        collateralConfig: sol_collateral_config,
+        position: dsc_position,
+        priceFeed: sol_price_feed,
         mint: dsc_mint,
+        surplus: dsc_surplus,
         destination: dsc_token_ata,
-        payer: provider.wallet.publicKey,
+        owner: provider.wallet.publicKey,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -67,6 +116,89 @@ describe("Asset Minting Tests", () => {
       .rpc();
   };
 
+  // DSC is only minted against collateral, so back a position with SOL first
+  const get_dsc = async () => {
+    await stable_coin_program.methods
+      .initializeConfig({
+        closeFactorBps: new BN(5_000),
+        globalDebtCeiling: new BN(1_000_000_000_000),
+      })
+      .accountsStrict({
+        config: dsc_config,
+        solVault: sol_vault,
+        program: stable_coin_program.programId,
+        programData: program_data(stable_coin_program.programId),
+        admin: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await stable_coin_program.methods
+      .initializeSurplus()
+      .accountsStrict({
+        config: dsc_config,
+        surplus: dsc_surplus,
+        mint: dsc_mint,
+        payer: signer,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await stable_coin_program.methods
+      .initializePriceFeed({
+        subject: SystemProgram.programId,
+        updaters: [signer],
+        maxStaleness: new BN(60),
+      })
+      .accountsStrict({
+        priceFeed: sol_price_feed,
+        authority: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await stable_coin_program.methods
+      .addCollateralType({
+        collateralMint: SystemProgram.programId,
+        params: {
+          liquidationRatioBps: new BN(15_000),
+          liquidationBonusBps: new BN(1_000),
+          debtCeiling: new BN(1_000_000_000_000),
+          enabled: true,
+        },
+      })
+      .accountsStrict({
+        config: dsc_config,
+        collateralConfig: sol_collateral_config,
+        collateralMint: null,
+        priceFeed: sol_price_feed,
+        admin: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await stable_coin_program.methods
+      .openPosition()
+      .accountsStrict({
+        collateralConfig: sol_collateral_config,
+        position: dsc_position,
+        owner: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await stable_coin_program.methods
+      .depositCollateral(new BN(LAMPORTS_PER_SOL))
+      .accountsStrict({
+        collateralConfig: sol_collateral_config,
+        position: dsc_position,
+        solVault: sol_vault,
+        collateralVault: null,
+        ownerCollateralAta: null,
+        owner: signer,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await mint_dsc(10);
+  };
+
   const init_dsc_vault = async () => {
     let tx1 = await indie_games_program.methods
       .initializeDscVault()
@@ -108,11 +240,17 @@ describe("Asset Minting Tests", () => {
         tradeOption: true,
         collateralOption: false,
         collateralRatio: new BN(0),
+        royaltyBps: 0,
+        royaltyRecipient: signer,
+        royaltyEnforced: false,
+        maxSupply: null,
+        maxPerWallet: null,
       })
       .accountsStrict({
         assetAccount: asset_data_account,
         mint: asset_mint,
         gameAccount: game_acc,
+        signerRole: null,
         creator: signer,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
@@ -127,22 +265,25 @@ describe("Asset Minting Tests", () => {
         .mintAssetAsOwner({
           amount: new BN(10),
           assetName: asset.name,
-          gameName: game.name,
           holder: signer,
         })
         .accountsStrict({
           mint: asset_mint,
           assetAccount: asset_data_account,
           destinationAta: asset_ata,
-          collateralTokenAccount: dsc_token_vault,
+          collateralVault: null,
           userDscTokenAta: dsc_token_ata,
+          priceFeed: null,
           destinationAtaAuthority: asset_ata_auth,
+          walletMintCount: wallet_mint_count,
           gameAccount: game_acc,
+          signerRole: null,
           user: signer,
           systemProgram: SystemProgram.programId,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           rent: SYSVAR_RENT_PUBKEY,
           tokenProgram: TOKEN_PROGRAM_ID,
+          assetTokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
     } catch (error) {
@@ -162,6 +303,594 @@ describe("Asset Minting Tests", () => {
     }
   };
 
+  // stands in for an off-chain updater so tests can move prices
+  const push_mock_price = async (price: number, confidence: number) => {
+    await stable_coin_program.methods
+      .pushPrice({ price: new BN(price), confidence: new BN(confidence) })
+      .accountsStrict({
+        priceFeed: price_feed,
+        updater: signer,
+      })
+      .rpc();
+  };
+
+  // listings and escrows are numbered by the global marketplace
+  const listing_pda = (id: BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(id.toString())],
+      indie_games_program.programId
+    )[0];
+
+  // escrow token accounts are signed for by a PDA of their own address
+  const create_escrow = async (mint: PublicKey, token_program: PublicKey) => {
+    const escrow = Keypair.generate();
+    const [escrow_authority] = PublicKey.findProgramAddressSync(
+      [escrow.publicKey.toBuffer()],
+      indie_games_program.programId
+    );
+    await createAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      escrow_authority,
+      escrow,
+      undefined,
+      token_program
+    );
+    return [escrow.publicKey, escrow_authority];
+  };
+
+  // what a royalty-enforced mint's transfer hook resolves on every transfer
+  const hook_accounts = (asset_account: PublicKey, mint: PublicKey) => {
+    const [extra_account_metas] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), mint.toBuffer()],
+      hook_program.programId
+    );
+    return [asset_account, hook_program.programId, extra_account_metas].map(
+      (pubkey) => ({ pubkey, isSigner: false, isWritable: false })
+    );
+  };
+
+  const sleep = (ms: number) =>
+    new Promise((resolve) => setTimeout(resolve, ms));
+
+  const auction_pdas = (id: BN) => {
+    const [auction] = PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), id.toArrayLike(Buffer, "le", 8)],
+      indie_games_program.programId
+    );
+    const [bid_vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bid_vault"), auction.toBuffer()],
+      indie_games_program.programId
+    );
+    return [auction, bid_vault];
+  };
+
+  const create_auction = async (amount: number, end_time: number) => {
+    const [escrow, escrow_authority] = await create_escrow(
+      asset_mint,
+      TOKEN_PROGRAM_ID
+    );
+    const id = (
+      await indie_games_program.account.marketplace.fetch(marketplace)
+    ).currentListingId;
+    const [auction, bid_vault] = auction_pdas(id);
+    await indie_games_program.methods
+      .createAuction({
+        assetName: asset.name,
+        assetGameId: game_acc,
+        amount: new BN(amount),
+        reservePrice: new BN(100),
+        minBidIncrement: new BN(10),
+        startTime: new BN(Math.floor(Date.now() / 1000) - 10),
+        endTime: new BN(end_time),
+      })
+      .accountsStrict({
+        marketplace,
+        auction,
+        assetAccount: asset_data_account,
+        assetMint: asset_mint,
+        marketAssetAta: escrow,
+        marketAssetAtaAuthority: escrow_authority,
+        sellerAssetAta: asset_ata,
+        sellerAssetAtaAuthority: asset_ata_auth,
+        dscMint: dsc_mint,
+        sellerDscAta: dsc_token_ata,
+        bidVault: bid_vault,
+        seller: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return { auction, bid_vault, escrow, escrow_authority };
+  };
+
+  // lists from the provider wallet's holding of the test asset, priced in DSC
+  // unless `terms` says otherwise
+  const list_asset = async (
+    amount: number,
+    terms: {
+      salePrice?: number;
+      dutchAuction?: any;
+      expiresAt?: number;
+      paymentMint?: PublicKey;
+    } = {}
+  ) => {
+    const [escrow, escrow_authority] = await create_escrow(
+      asset_mint,
+      TOKEN_PROGRAM_ID
+    );
+    const id = (
+      await indie_games_program.account.marketplace.fetch(marketplace)
+    ).currentListingId;
+    const sale_acc = listing_pda(id);
+    const payment_mint = terms.paymentMint ?? dsc_mint;
+    const paid_in_sol = payment_mint.equals(SystemProgram.programId);
+    await indie_games_program.methods
+      .listAsset({
+        assetName: asset.name,
+        salePrice: new BN(terms.salePrice ?? 1_000),
+        saleAmount: new BN(amount),
+        assetGameId: game_acc,
+        dscCreditAta: paid_in_sol ? signer : dsc_token_ata,
+        assetMint: asset_mint,
+        dutchAuction: terms.dutchAuction ?? null,
+        expiresAt: terms.expiresAt ? new BN(terms.expiresAt) : null,
+        paymentMint: payment_mint,
+      })
+      .accountsStrict({
+        marketAssetAta: escrow,
+        assetMint: asset_mint,
+        marketAssetAtaAuthority: escrow_authority,
+        assetAccount: asset_data_account,
+        sellerAssetAta: asset_ata,
+        sellerDscAta: paid_in_sol ? null : dsc_token_ata,
+        sellerAssetAtaAuthority: asset_ata_auth,
+        seller: signer,
+        marketplace,
+        gameMarketplace: null,
+        priceFeed: price_feed,
+        saleAcc: sale_acc,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return { sale_acc, escrow, escrow_authority };
+  };
+
+  const buy_listing = async (
+    listing: {
+      sale_acc: PublicKey;
+      escrow: PublicKey;
+      escrow_authority: PublicKey;
+    },
+    quantity: number,
+    max_price: number,
+    paid_in_sol = false
+  ) => {
+    const buyer_asset_ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        asset_mint,
+        buyer.publicKey
+      )
+    ).address;
+    await indie_games_program.methods
+      .buyFromMarketplace({
+        quantity: new BN(quantity),
+        maxPrice: new BN(max_price),
+      })
+      .accountsStrict({
+        buyerDscAta: paid_in_sol ? null : buyer_dsc_ata,
+        sellerDscAta: paid_in_sol ? null : dsc_token_ata,
+        feeTreasuryAta: paid_in_sol ? null : fee_treasury_ata,
+        // the shared asset charges no royalty, so neither account is needed
+        royaltyAta: null,
+        feeTreasury: paid_in_sol ? fee_treasury.publicKey : null,
+        royaltyRecipient: null,
+        marketplace,
+        assetAccount: asset_data_account,
+        buyerAssetAta: buyer_asset_ata,
+        assetHoldingAta: listing.escrow,
+        assetMint: asset_mint,
+        assetHoldingAtaAuthority: listing.escrow_authority,
+        saleAcc: listing.sale_acc,
+        seller: signer,
+        user: buyer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+    return buyer_asset_ata;
+  };
+
+  const make_offer = async (amount: number, expires_at: number) => {
+    const id = (
+      await indie_games_program.account.marketplace.fetch(marketplace)
+    ).currentListingId;
+    const [offer] = PublicKey.findProgramAddressSync(
+      [Buffer.from("offer"), id.toArrayLike(Buffer, "le", 8)],
+      indie_games_program.programId
+    );
+    const [offer_vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("offer_vault"), offer.toBuffer()],
+      indie_games_program.programId
+    );
+    const buyer_asset_ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        asset_mint,
+        buyer.publicKey
+      )
+    ).address;
+    await indie_games_program.methods
+      .makeOffer({
+        assetName: asset.name,
+        assetGameId: game_acc,
+        holder: null,
+        unitPrice: new BN(200),
+        amount: new BN(amount),
+        expiresAt: new BN(expires_at),
+      })
+      .accountsStrict({
+        marketplace,
+        offer,
+        assetAccount: asset_data_account,
+        assetMint: asset_mint,
+        buyerAssetAta: buyer_asset_ata,
+        buyerDscAta: buyer_dsc_ata,
+        dscMint: dsc_mint,
+        offerVault: offer_vault,
+        buyer: buyer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+    return { offer, offer_vault, buyer_asset_ata };
+  };
+
+  // offers one token of the test asset plus `offered_dsc` for the buyer's DSC
+  const open_swap = async (offered_dsc: number, requested_dsc: number) => {
+    const id = (
+      await indie_games_program.account.marketplace.fetch(marketplace)
+    ).currentListingId;
+    const [swap] = PublicKey.findProgramAddressSync(
+      [Buffer.from("swap"), id.toArrayLike(Buffer, "le", 8)],
+      indie_games_program.programId
+    );
+    const [swap_vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("swap_vault"), swap.toBuffer()],
+      indie_games_program.programId
+    );
+    const escrow = await createAccount(
+      provider.connection,
+      provider.wallet.payer,
+      asset_mint,
+      swap,
+      Keypair.generate()
+    );
+    await indie_games_program.methods
+      .createSwap({
+        counterparty: buyer.publicKey,
+        offered: [{ assetAccount: asset_data_account, amount: new BN(1) }],
+        offeredDsc: new BN(offered_dsc),
+        requested: [],
+        requestedDsc: new BN(requested_dsc),
+      })
+      .accountsStrict({
+        marketplace,
+        swap,
+        dscMint: dsc_mint,
+        swapVault: swap_vault,
+        initiatorDscAta: dsc_token_ata,
+        initiator: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: asset_data_account, isSigner: false, isWritable: false },
+        { pubkey: asset_mint, isSigner: false, isWritable: false },
+        { pubkey: asset_ata, isSigner: false, isWritable: true },
+        { pubkey: asset_ata_auth, isSigner: false, isWritable: false },
+        { pubkey: escrow, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+    return { swap, swap_vault, escrow };
+  };
+
+  // another asset of the test game, tradable and unbacked unless `terms` says
+  // otherwise
+  const create_asset = async (
+    name: string,
+    terms: {
+      tradeOption?: boolean;
+      collateralOption?: boolean;
+      collateralRatio?: number;
+    },
+    token_program: PublicKey
+  ) => {
+    const [account] = PublicKey.findProgramAddressSync(
+      [Buffer.from(name), game_acc.toBuffer()],
+      indie_games_program.programId
+    );
+    const [mint] = PublicKey.findProgramAddressSync(
+      [game_acc.toBuffer(), account.toBuffer()],
+      indie_games_program.programId
+    );
+    await indie_games_program.methods
+      .initializeAssets({
+        gameId: game_acc,
+        name,
+        symbol: "EXT",
+        uri: asset.uri,
+        price: asset.price,
+        score: asset.score,
+        tradeOption: terms.tradeOption ?? true,
+        collateralOption: terms.collateralOption ?? false,
+        collateralRatio: new BN(terms.collateralRatio ?? 0),
+        royaltyBps: 0,
+        royaltyRecipient: signer,
+        royaltyEnforced: false,
+        maxSupply: null,
+        maxPerWallet: null,
+      })
+      .accountsStrict({
+        assetAccount: account,
+        mint,
+        gameAccount: game_acc,
+        signerRole: null,
+        creator: signer,
+        tokenProgram: token_program,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return { account, mint };
+  };
+
+  // the provider wallet's AssetAuthority and the account it holds `mint` in
+  const holding_of = async (mint: PublicKey, token_program: PublicKey) => {
+    const [authority] = PublicKey.findProgramAddressSync(
+      [signer.toBuffer(), mint.toBuffer()],
+      indie_games_program.programId
+    );
+    const ata = await getAssociatedTokenAddress(
+      mint,
+      authority,
+      true,
+      token_program
+    );
+    return { authority, ata };
+  };
+
+  // mints into the provider wallet's holding, paying collateral into
+  // `collateral_vault` when one is given
+  const mint_holding = async (
+    name: string,
+    created: { account: PublicKey; mint: PublicKey },
+    amount: number,
+    token_program: PublicKey,
+    collateral_vault: PublicKey | null = null,
+    feed: PublicKey | null = null
+  ) => {
+    const holding = await holding_of(created.mint, token_program);
+    const [mint_count] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("wallet_mint"),
+        created.account.toBuffer(),
+        signer.toBuffer(),
+      ],
+      indie_games_program.programId
+    );
+    await indie_games_program.methods
+      .mintAssetAsOwner({
+        amount: new BN(amount),
+        assetName: name,
+        holder: signer,
+      })
+      .accountsStrict({
+        mint: created.mint,
+        assetAccount: created.account,
+        destinationAta: holding.ata,
+        collateralVault: collateral_vault,
+        userDscTokenAta: collateral_vault ? dsc_token_ata : null,
+        priceFeed: feed,
+        destinationAtaAuthority: holding.authority,
+        walletMintCount: mint_count,
+        gameAccount: game_acc,
+        signerRole: null,
+        user: signer,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: token_program,
+      })
+      .rpc();
+    return holding;
+  };
+
+  // per-asset DSC vault collateralized mints pay into
+  const open_collateral_vault = async (name: string, account: PublicKey) => {
+    const [collateral_vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_vault"), account.toBuffer()],
+      indie_games_program.programId
+    );
+    await indie_games_program.methods
+      .initializeCollateralVault({ gameId: game_acc, assetName: name })
+      .accountsStrict({
+        assetAccount: account,
+        collateralVault: collateral_vault,
+        vaultAuthority: dsc_vault_authority,
+        dscMint: dsc_mint,
+        payer: signer,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    return collateral_vault;
+  };
+
+  // fails the test unless `attempt` is rejected with the program error `code`
+  const expect_error = async (attempt: Promise<unknown>, code: string) => {
+    let rejected = false;
+    try {
+      await attempt;
+    } catch (error) {
+      rejected = true;
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+    expect(rejected).to.equal(true);
+  };
+
+  const stable_vault_authority = PublicKey.findProgramAddressSync(
+    [Buffer.from("vault_authority")],
+    stable_coin_program.programId
+  )[0];
+
+  type Borrower = {
+    wallet: Keypair;
+    position: PublicKey;
+    dsc_ata: PublicKey;
+  };
+
+  // a fresh wallet with a SOL position holding `lamports` of collateral
+  const open_borrower = async (lamports: number): Promise<Borrower> => {
+    const wallet = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        lamports + LAMPORTS_PER_SOL
+      )
+    );
+    const [position] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("position"),
+        wallet.publicKey.toBuffer(),
+        SystemProgram.programId.toBuffer(),
+      ],
+      stable_coin_program.programId
+    );
+    await stable_coin_program.methods
+      .openPosition()
+      .accountsStrict({
+        collateralConfig: sol_collateral_config,
+        position,
+        owner: wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([wallet])
+      .rpc();
+    await stable_coin_program.methods
+      .depositCollateral(new BN(lamports))
+      .accountsStrict({
+        collateralConfig: sol_collateral_config,
+        position,
+        solVault: sol_vault,
+        collateralVault: null,
+        ownerCollateralAta: null,
+        owner: wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([wallet])
+      .rpc();
+    const dsc_ata = await getAssociatedTokenAddress(dsc_mint, wallet.publicKey);
+    return { wallet, position, dsc_ata };
+  };
+
+  // DSC base units per SOL
+  const push_sol_price = (price: number) =>
+    stable_coin_program.methods
+      .pushPrice({ price: new BN(price), confidence: new BN(0) })
+      .accountsStrict({ priceFeed: sol_price_feed, updater: signer })
+      .rpc();
+
+  const borrow = (borrower: Borrower, amount: number) =>
+    stable_coin_program.methods
+      .mintTokens(new BN(amount))
+      .accountsStrict({
+        config: dsc_config,
+        collateralConfig: sol_collateral_config,
+        position: borrower.position,
+        priceFeed: sol_price_feed,
+        mint: dsc_mint,
+        surplus: dsc_surplus,
+        destination: borrower.dsc_ata,
+        owner: borrower.wallet.publicKey,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([borrower.wallet])
+      .rpc();
+
+  const repay = (borrower: Borrower, amount: number) =>
+    stable_coin_program.methods
+      .repay(new BN(amount))
+      .accountsStrict({
+        config: dsc_config,
+        collateralConfig: sol_collateral_config,
+        position: borrower.position,
+        mint: dsc_mint,
+        surplus: dsc_surplus,
+        ownerDscAta: borrower.dsc_ata,
+        owner: borrower.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([borrower.wallet])
+      .rpc();
+
+  const withdraw = (borrower: Borrower, lamports: number) =>
+    stable_coin_program.methods
+      .withdrawCollateral(new BN(lamports))
+      .accountsStrict({
+        config: dsc_config,
+        collateralConfig: sol_collateral_config,
+        position: borrower.position,
+        priceFeed: sol_price_feed,
+        mint: dsc_mint,
+        surplus: dsc_surplus,
+        solVault: sol_vault,
+        collateralVault: null,
+        ownerCollateralAta: null,
+        vaultAuthority: stable_vault_authority,
+        owner: borrower.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([borrower.wallet])
+      .rpc();
+
+  const liquidate = (borrower: Borrower, amount: number) =>
+    stable_coin_program.methods
+      .liquidate(new BN(amount))
+      .accountsStrict({
+        config: dsc_config,
+        collateralConfig: sol_collateral_config,
+        position: borrower.position,
+        priceFeed: sol_price_feed,
+        mint: dsc_mint,
+        surplus: dsc_surplus,
+        liquidatorDscAta: dsc_token_ata,
+        solVault: sol_vault,
+        collateralVault: null,
+        liquidatorCollateralAta: null,
+        vaultAuthority: stable_vault_authority,
+        liquidator: signer,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
   before(async () => {
     const [gamepda, gameBump] = PublicKey.findProgramAddressSync(
       [signer.toBuffer(), Buffer.from(game.name)],
@@ -183,6 +912,38 @@ describe("Asset Minting Tests", () => {
       stable_coin_program.programId
     );
     dsc_mint = dscMintPda;
+    [dsc_config] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      stable_coin_program.programId
+    );
+    [dsc_position] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("position"),
+        signer.toBuffer(),
+        SystemProgram.programId.toBuffer(),
+      ],
+      stable_coin_program.programId
+    );
+    [sol_price_feed] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("price_feed"),
+        SystemProgram.programId.toBuffer(),
+        signer.toBuffer(),
+      ],
+      stable_coin_program.programId
+    );
+    [dsc_surplus] = PublicKey.findProgramAddressSync(
+      [Buffer.from("surplus")],
+      stable_coin_program.programId
+    );
+    [sol_collateral_config] = PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), SystemProgram.programId.toBuffer()],
+      stable_coin_program.programId
+    );
+    [sol_vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sol_vault")],
+      stable_coin_program.programId
+    );
     const [vault_pda, vault_bump] = PublicKey.findProgramAddressSync(
       [Buffer.from("token_vault")],
       indie_games_program.programId
@@ -199,6 +960,24 @@ describe("Asset Minting Tests", () => {
       indie_games_program.programId
     );
     asset_ata_auth = assetAuth;
+    const [walletMintCount] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("wallet_mint"),
+        asset_data_account.toBuffer(),
+        signer.toBuffer(),
+      ],
+      indie_games_program.programId
+    );
+    wallet_mint_count = walletMintCount;
+    const [priceFeedPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("price_feed"),
+        asset_data_account.toBuffer(),
+        signer.toBuffer(),
+      ],
+      stable_coin_program.programId
+    );
+    price_feed = priceFeedPda;
     const tokenAccount = await getAssociatedTokenAddress(
       asset_mint,
       assetAuth,
@@ -239,7 +1018,255 @@ describe("Asset Minting Tests", () => {
     expect(acc.amount.toString()).to.equal("10");
   });
 
-  it("initializes game", async () => {
+  it("swaps an approved stablecoin for dsc through the psm", async () => {
+    const stable_mint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      signer,
+      null,
+      6
+    );
+    const user_stable_ata = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      stable_mint,
+      signer
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      stable_mint,
+      user_stable_ata.address,
+      signer,
+      1_000
+    );
+    const [psm_reserve] = PublicKey.findProgramAddressSync(
+      [Buffer.from("psm"), stable_mint.toBuffer()],
+      stable_coin_program.programId
+    );
+    const [psm_vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("psm_vault"), stable_mint.toBuffer()],
+      stable_coin_program.programId
+    );
+    const [vault_authority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_authority")],
+      stable_coin_program.programId
+    );
+    await stable_coin_program.methods
+      .initializePsmReserve({ feeBps: new BN(100), cap: new BN(10_000) })
+      .accountsStrict({
+        config: dsc_config,
+        psmReserve: psm_reserve,
+        psmVault: psm_vault,
+        stableMint: stable_mint,
+        vaultAuthority: vault_authority,
+        admin: signer,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const before = await getAccount(provider.connection, dsc_token_ata);
+    await stable_coin_program.methods
+      .swapStableForDsc(new BN(1_000))
+      .accountsStrict({
+        psmReserve: psm_reserve,
+        psmVault: psm_vault,
+        userStableAta: user_stable_ata.address,
+        mint: dsc_mint,
+        userDscAta: dsc_token_ata,
+        surplus: dsc_surplus,
+        user: signer,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const after = await getAccount(provider.connection, dsc_token_ata);
+    // 1% fee on 1_000 goes to the surplus account
+    expect((after.amount - before.amount).toString()).to.equal("990");
+    await stable_coin_program.methods
+      .swapDscForStable(new BN(990))
+      .accountsStrict({
+        psmReserve: psm_reserve,
+        psmVault: psm_vault,
+        userStableAta: user_stable_ata.address,
+        mint: dsc_mint,
+        userDscAta: dsc_token_ata,
+        surplus: dsc_surplus,
+        vaultAuthority: vault_authority,
+        user: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const stable = await getAccount(provider.connection, user_stable_ata.address);
+    expect(stable.amount.toString()).to.equal("980");
+  });
+
+  it("repays a DSC position and withdraws its collateral", async () => {
+    const borrower = await open_borrower(LAMPORTS_PER_SOL);
+    await push_sol_price(150_000_000);
+    await borrow(borrower, 50_000_000);
+    // 1 SOL at 150 DSC backs at most 100 DSC at the 150% ratio
+    await expect_error(borrow(borrower, 60_000_000), "Undercollateralized");
+    await expect_error(withdraw(borrower, 900_000_000), "Undercollateralized");
+
+    await repay(borrower, 20_000_000);
+    let position = await stable_coin_program.account.position.fetch(
+      borrower.position
+    );
+    expect(position.normalizedDebt.toString()).to.equal("30000000");
+    await repay(borrower, 30_000_000);
+    position = await stable_coin_program.account.position.fetch(
+      borrower.position
+    );
+    expect(position.normalizedDebt.toString()).to.equal("0");
+
+    // debt-free, so the whole deposit comes back
+    const before = await provider.connection.getBalance(
+      borrower.wallet.publicKey
+    );
+    await withdraw(borrower, LAMPORTS_PER_SOL);
+    const after = await provider.connection.getBalance(
+      borrower.wallet.publicKey
+    );
+    expect(after - before).to.equal(LAMPORTS_PER_SOL);
+    position = await stable_coin_program.account.position.fetch(
+      borrower.position
+    );
+    expect(position.collateral.toString()).to.equal("0");
+  });
+
+  it("liquidates an unhealthy DSC position", async () => {
+    // the provider wallet liquidates with DSC minted against its own position
+    await mint_dsc(20_000_000);
+    const borrower = await open_borrower(LAMPORTS_PER_SOL);
+    await push_sol_price(150_000_000);
+    await borrow(borrower, 90_000_000);
+    await expect_error(liquidate(borrower, 9_000_000), "PositionHealthy");
+
+    // 120 DSC per SOL leaves 120 of value against the 135 the ratio needs
+    await push_sol_price(120_000_000);
+    // the close factor caps a single liquidation at half of the 90 DSC debt
+    await expect_error(liquidate(borrower, 46_000_000), "ExceedsCloseFactor");
+
+    const vault_before = await provider.connection.getBalance(sol_vault);
+    const dsc_before = await getAccount(provider.connection, dsc_token_ata);
+    await liquidate(borrower, 10_000_000);
+    const vault_after = await provider.connection.getBalance(sol_vault);
+    const dsc_after = await getAccount(provider.connection, dsc_token_ata);
+
+    // 10 DSC plus the 10% bonus at 120 DSC per SOL
+    const seized = 91_666_666;
+    expect(vault_before - vault_after).to.equal(seized);
+    expect((dsc_before.amount - dsc_after.amount).toString()).to.equal(
+      "10000000"
+    );
+    const position = await stable_coin_program.account.position.fetch(
+      borrower.position
+    );
+    expect(position.collateral.toNumber()).to.equal(LAMPORTS_PER_SOL - seized);
+    expect(position.normalizedDebt.toString()).to.equal("80000000");
+  });
+
+  it("stops minting DSC at the debt ceilings", async () => {
+    const borrower = await open_borrower(LAMPORTS_PER_SOL);
+    await push_sol_price(150_000_000);
+    const WAD = new BN("1000000000000000000");
+    const set_type_ceiling = (debt_ceiling: BN) =>
+      stable_coin_program.methods
+        .updateCollateralType({
+          liquidationRatioBps: new BN(15_000),
+          liquidationBonusBps: new BN(1_000),
+          debtCeiling: debt_ceiling,
+          enabled: true,
+        })
+        .accountsStrict({
+          config: dsc_config,
+          collateralConfig: sol_collateral_config,
+          priceFeed: null,
+          admin: signer,
+        })
+        .rpc();
+    const set_global_ceiling = (debt_ceiling: BN) =>
+      stable_coin_program.methods
+        .updateConfig({
+          closeFactorBps: new BN(5_000),
+          globalDebtCeiling: debt_ceiling,
+        })
+        .accountsStrict({ config: dsc_config, admin: signer })
+        .rpc();
+
+    // pin the SOL type's ceiling at what it already owes
+    const collateral = await stable_coin_program.account.collateralConfig.fetch(
+      sol_collateral_config
+    );
+    const type_debt = collateral.totalNormalizedDebt
+      .mul(collateral.rateIndex)
+      .add(WAD.subn(1))
+      .div(WAD);
+    await set_type_ceiling(type_debt);
+    await expect_error(borrow(borrower, 1_000_000), "DebtCeilingExceeded");
+    await set_type_ceiling(new BN(1_000_000_000_000));
+
+    const config = await stable_coin_program.account.stablecoinConfig.fetch(
+      dsc_config
+    );
+    await set_global_ceiling(config.totalDebt);
+    await expect_error(
+      borrow(borrower, 1_000_001),
+      "GlobalDebtCeilingExceeded"
+    );
+    await set_global_ceiling(new BN(1_000_000_000_000));
+
+    await borrow(borrower, 1_000_002);
+    const position = await stable_coin_program.account.position.fetch(
+      borrower.position
+    );
+    expect(position.normalizedDebt.toString()).to.equal("1000002");
+  });
+
+  it("settles stability fees when the rate changes", async () => {
+    const WAD = new BN("1000000000000000000");
+    const set_fee = (rate: BN) =>
+      stable_coin_program.methods
+        .setStabilityFee(rate)
+        .accountsStrict({
+          config: dsc_config,
+          collateralConfig: sol_collateral_config,
+          mint: dsc_mint,
+          surplus: dsc_surplus,
+          admin: signer,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    await expect_error(set_fee(WAD.subn(1)), "InvalidConfig");
+
+    // the steepest allowed rate, so a few seconds accrue a visible fee
+    await set_fee(WAD.add(new BN(22_000_000_000)));
+    const before = await stable_coin_program.account.collateralConfig.fetch(
+      sol_collateral_config
+    );
+    const surplus_before = await getAccount(provider.connection, dsc_surplus);
+    await sleep(3000);
+    // dropping the fee still charges the seconds that ran at the old rate
+    await set_fee(WAD);
+    const after = await stable_coin_program.account.collateralConfig.fetch(
+      sol_collateral_config
+    );
+    const surplus_after = await getAccount(provider.connection, dsc_surplus);
+
+    expect(after.rateIndex.gt(before.rateIndex)).to.equal(true);
+    expect(after.stabilityFeeRate.toString()).to.equal(WAD.toString());
+    const fee = before.totalNormalizedDebt
+      .mul(after.rateIndex.sub(before.rateIndex))
+      .div(WAD);
+    expect(fee.gtn(0)).to.equal(true);
+    expect((surplus_after.amount - surplus_before.amount).toString()).to.equal(
+      fee.toString()
+    );
+  });
+
+  it("initializes game", async () => {
     await init_game();
     let gameAcc = await indie_games_program.account.gameState.fetch(game_acc);
     expect(gameAcc.owner.toString()).to.equal(signer.toString());
@@ -256,4 +1283,1102 @@ describe("Asset Minting Tests", () => {
   it(" mint assets as a owner", async () => {
     await mint_assets();
   });
+
+  it("prices assets from a mock updated feed", async () => {
+    await stable_coin_program.methods
+      .initializePriceFeed({
+        subject: asset_data_account,
+        updaters: [signer],
+        maxStaleness: new BN(60),
+      })
+      .accountsStrict({
+        priceFeed: price_feed,
+        authority: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await indie_games_program.methods
+      .setAssetPriceFeed({ assetName: asset.name })
+      .accountsStrict({
+        priceFeed: price_feed,
+        assetAccount: asset_data_account,
+        gameAccount: game_acc,
+        authority: signer,
+      })
+      .rpc();
+    // DSC base units per token, 12 +/- 1 DSC
+    await push_mock_price(12_000_000, 1_000_000);
+    let feed = await stable_coin_program.account.priceFeed.fetch(price_feed);
+    expect(feed.price.toString()).to.equal("12000000");
+    expect(feed.confidence.toString()).to.equal("1000000");
+    let assetAcc = await indie_games_program.account.assetData.fetch(
+      asset_data_account
+    );
+    expect(assetAcc.priceFeed.toString()).to.equal(price_feed.toString());
+  });
+
+  it("updates game metadata", async () => {
+    await indie_games_program.methods
+      .updateGame({ name: null, description: "Updated Description" })
+      .accountsStrict({
+        gameAccount: game_acc,
+        owner: signer,
+      })
+      .rpc();
+    let gameAcc = await indie_games_program.account.gameState.fetch(game_acc);
+    expect(gameAcc.description).to.equal("Updated Description");
+    expect(gameAcc.name).to.equal(game.name);
+  });
+
+  it("hands over game ownership in two steps", async () => {
+    const new_owner = Keypair.generate();
+    await indie_games_program.methods
+      .proposeGameOwner({ newOwner: new_owner.publicKey })
+      .accountsStrict({
+        gameAccount: game_acc,
+        owner: signer,
+      })
+      .rpc();
+    await indie_games_program.methods
+      .acceptGameOwner()
+      .accountsStrict({
+        gameAccount: game_acc,
+        newOwner: new_owner.publicKey,
+      })
+      .signers([new_owner])
+      .rpc();
+    let gameAcc = await indie_games_program.account.gameState.fetch(game_acc);
+    expect(gameAcc.owner.toString()).to.equal(new_owner.publicKey.toString());
+
+    // hand it back so later tests keep running as the provider wallet
+    await indie_games_program.methods
+      .proposeGameOwner({ newOwner: signer })
+      .accountsStrict({
+        gameAccount: game_acc,
+        owner: new_owner.publicKey,
+      })
+      .signers([new_owner])
+      .rpc();
+    await indie_games_program.methods
+      .acceptGameOwner()
+      .accountsStrict({
+        gameAccount: game_acc,
+        newOwner: signer,
+      })
+      .rpc();
+  });
+
+  it("opens the global marketplace", async () => {
+    [marketplace] = PublicKey.findProgramAddressSync(
+      [Buffer.from("marketplace")],
+      indie_games_program.programId
+    );
+    await indie_games_program.methods
+      .initializeMarketplace({
+        feeBps: 100,
+        feeTreasury: fee_treasury.publicKey,
+        allowedPaymentMints: [dsc_mint],
+        minPrice: new BN(1),
+        maxPrice: new BN(0),
+        allowForeignAssets: false,
+        oracleFloorBps: 0,
+      })
+      .accountsStrict({
+        marketplace,
+        program: indie_games_program.programId,
+        programData: program_data(indie_games_program.programId),
+        initializer: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    fee_treasury_ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        dsc_mint,
+        fee_treasury.publicKey
+      )
+    ).address;
+    // a second wallet to buy, bid and trade against the provider wallet
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(
+        buyer.publicKey,
+        2 * LAMPORTS_PER_SOL
+      )
+    );
+    buyer_dsc_ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        dsc_mint,
+        buyer.publicKey
+      )
+    ).address;
+    await mint_dsc(100_000);
+    await transfer(
+      provider.connection,
+      provider.wallet.payer,
+      dsc_token_ata,
+      buyer_dsc_ata,
+      provider.wallet.payer,
+      50_000
+    );
+    let market = await indie_games_program.account.marketplace.fetch(
+      marketplace
+    );
+    expect(market.admin.toString()).to.equal(signer.toString());
+  });
+
+  it("sells a royalty-enforced asset through its transfer hook", async () => {
+    const hooked_name = "hooked";
+    const [hooked_account] = PublicKey.findProgramAddressSync(
+      [Buffer.from(hooked_name), game_acc.toBuffer()],
+      indie_games_program.programId
+    );
+    const [hooked_mint] = PublicKey.findProgramAddressSync(
+      [game_acc.toBuffer(), hooked_account.toBuffer()],
+      indie_games_program.programId
+    );
+    await indie_games_program.methods
+      .initializeAssets({
+        gameId: game_acc,
+        name: hooked_name,
+        symbol: "HKD",
+        uri: asset.uri,
+        price: asset.price,
+        score: asset.score,
+        tradeOption: true,
+        collateralOption: false,
+        collateralRatio: new BN(0),
+        royaltyBps: 500,
+        royaltyRecipient: signer,
+        royaltyEnforced: true,
+        maxSupply: null,
+        maxPerWallet: null,
+      })
+      .accountsStrict({
+        assetAccount: hooked_account,
+        mint: hooked_mint,
+        gameAccount: game_acc,
+        signerRole: null,
+        creator: signer,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    // the extra follow-up step royalty-enforced assets need before any transfer
+    const [extra_account_metas] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), hooked_mint.toBuffer()],
+      hook_program.programId
+    );
+    await hook_program.methods
+      .initializeExtraAccountMetaList()
+      .accountsStrict({
+        extraAccountMetaList: extra_account_metas,
+        mint: hooked_mint,
+        assetAccount: hooked_account,
+        payer: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const [holding_authority] = PublicKey.findProgramAddressSync(
+      [signer.toBuffer(), hooked_mint.toBuffer()],
+      indie_games_program.programId
+    );
+    const holding_ata = await getAssociatedTokenAddress(
+      hooked_mint,
+      holding_authority,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const [hooked_mint_count] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("wallet_mint"),
+        hooked_account.toBuffer(),
+        signer.toBuffer(),
+      ],
+      indie_games_program.programId
+    );
+    await indie_games_program.methods
+      .mintAssetAsOwner({
+        amount: new BN(5),
+        assetName: hooked_name,
+        holder: signer,
+      })
+      .accountsStrict({
+        mint: hooked_mint,
+        assetAccount: hooked_account,
+        destinationAta: holding_ata,
+        collateralVault: null,
+        userDscTokenAta: null,
+        priceFeed: null,
+        destinationAtaAuthority: holding_authority,
+        walletMintCount: hooked_mint_count,
+        gameAccount: game_acc,
+        signerRole: null,
+        user: signer,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const [escrow, escrow_authority] = await create_escrow(
+      hooked_mint,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const listing_id = (
+      await indie_games_program.account.marketplace.fetch(marketplace)
+    ).currentListingId;
+    const sale_acc = listing_pda(listing_id);
+    await indie_games_program.methods
+      .listAsset({
+        assetName: hooked_name,
+        salePrice: new BN(1_000),
+        saleAmount: new BN(2),
+        assetGameId: game_acc,
+        dscCreditAta: dsc_token_ata,
+        assetMint: hooked_mint,
+        dutchAuction: null,
+        expiresAt: null,
+        paymentMint: dsc_mint,
+      })
+      .accountsStrict({
+        marketAssetAta: escrow,
+        assetMint: hooked_mint,
+        marketAssetAtaAuthority: escrow_authority,
+        assetAccount: hooked_account,
+        sellerAssetAta: holding_ata,
+        sellerDscAta: dsc_token_ata,
+        sellerAssetAtaAuthority: holding_authority,
+        seller: signer,
+        marketplace,
+        gameMarketplace: null,
+        priceFeed: null,
+        saleAcc: sale_acc,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(hook_accounts(hooked_account, hooked_mint))
+      .rpc();
+
+    const buyer_asset_ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        hooked_mint,
+        buyer.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    const seller_before = await getAccount(provider.connection, dsc_token_ata);
+    await indie_games_program.methods
+      .buyFromMarketplace({ quantity: new BN(2), maxPrice: new BN(1_000) })
+      .accountsStrict({
+        buyerDscAta: buyer_dsc_ata,
+        sellerDscAta: dsc_token_ata,
+        feeTreasuryAta: fee_treasury_ata,
+        royaltyAta: dsc_token_ata,
+        feeTreasury: null,
+        royaltyRecipient: null,
+        marketplace,
+        assetAccount: hooked_account,
+        buyerAssetAta: buyer_asset_ata,
+        assetHoldingAta: escrow,
+        assetMint: hooked_mint,
+        assetHoldingAtaAuthority: escrow_authority,
+        saleAcc: sale_acc,
+        seller: signer,
+        user: buyer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(hook_accounts(hooked_account, hooked_mint))
+      .signers([buyer])
+      .rpc();
+    const bought = await getAccount(
+      provider.connection,
+      buyer_asset_ata,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(bought.amount.toString()).to.equal("2");
+    // the seller is also the royalty recipient, so only the 1% fee leaves
+    const seller_after = await getAccount(provider.connection, dsc_token_ata);
+    expect((seller_after.amount - seller_before.amount).toString()).to.equal(
+      "1980"
+    );
+    expect(
+      await indie_games_program.account.sale.fetchNullable(sale_acc)
+    ).to.equal(null);
+
+    // a wallet-signed transfer skips the marketplace, so the hook refuses it
+    const raw_transfer = createTransferCheckedInstruction(
+      buyer_asset_ata,
+      hooked_mint,
+      holding_ata,
+      buyer.publicKey,
+      1,
+      0,
+      [],
+      TOKEN_2022_PROGRAM_ID
+    );
+    raw_transfer.keys.push(...hook_accounts(hooked_account, hooked_mint));
+    let bypassed = true;
+    try {
+      await provider.sendAndConfirm(new Transaction().add(raw_transfer), [
+        buyer,
+      ]);
+    } catch (error) {
+      bypassed = false;
+    }
+    expect(bypassed).to.equal(false);
+  });
+
+  it("auctions assets to the highest bidder", async () => {
+    const end_time = Math.floor(Date.now() / 1000) + 5;
+    const { auction, bid_vault, escrow, escrow_authority } =
+      await create_auction(2, end_time);
+    const buyer_asset_ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        asset_mint,
+        buyer.publicKey
+      )
+    ).address;
+    // `outbid` is the wallet holding the current top bid, refunded to its ATA
+    const bid = async (amount: number, outbid: PublicKey | null) => {
+      await indie_games_program.methods
+        .placeBid({ amount: new BN(amount) })
+        .accountsStrict({
+          auction,
+          bidVault: bid_vault,
+          dscMint: dsc_mint,
+          bidderDscAta: buyer_dsc_ata,
+          bidderAssetAta: buyer_asset_ata,
+          previousBidder: outbid,
+          previousBidderDscAta: outbid
+            ? await getAssociatedTokenAddress(dsc_mint, outbid)
+            : null,
+          bidder: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+    };
+    const buyer_before = await getAccount(provider.connection, buyer_dsc_ata);
+    await bid(100, null);
+    // outbidding refunds the previous top bid in full
+    await bid(120, buyer.publicKey);
+    const buyer_bidding = await getAccount(provider.connection, buyer_dsc_ata);
+    expect((buyer_before.amount - buyer_bidding.amount).toString()).to.equal(
+      "120"
+    );
+    let auctionAcc = await indie_games_program.account.auction.fetch(auction);
+    expect(auctionAcc.highestBid.toString()).to.equal("120");
+
+    await sleep(7_000);
+    const treasury_before = await getAccount(
+      provider.connection,
+      fee_treasury_ata
+    );
+    await indie_games_program.methods
+      .settleAuction()
+      .accountsStrict({
+        auction,
+        marketplace,
+        assetAccount: asset_data_account,
+        marketAssetAta: escrow,
+        assetMint: asset_mint,
+        marketAssetAtaAuthority: escrow_authority,
+        assetDestinationAta: buyer_asset_ata,
+        bidVault: bid_vault,
+        sellerDscAta: dsc_token_ata,
+        feeTreasuryAta: fee_treasury_ata,
+        royaltyAta: dsc_token_ata,
+        seller: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const won = await getAccount(provider.connection, buyer_asset_ata);
+    expect(won.amount.toString()).to.equal("2");
+    const treasury_after = await getAccount(
+      provider.connection,
+      fee_treasury_ata
+    );
+    expect(
+      (treasury_after.amount - treasury_before.amount).toString()
+    ).to.equal("1");
+    expect(
+      await indie_games_program.account.auction.fetchNullable(auction)
+    ).to.equal(null);
+    expect(await provider.connection.getAccountInfo(bid_vault)).to.equal(null);
+  });
+
+  it("cancels an auction nobody bid on", async () => {
+    const before = await getAccount(provider.connection, asset_ata);
+    const { auction, bid_vault, escrow, escrow_authority } =
+      await create_auction(1, Math.floor(Date.now() / 1000) + 60);
+    await indie_games_program.methods
+      .cancelAuction()
+      .accountsStrict({
+        auction,
+        marketAssetAta: escrow,
+        assetMint: asset_mint,
+        marketAssetAtaAuthority: escrow_authority,
+        sellerAssetAta: asset_ata,
+        bidVault: bid_vault,
+        sellerDscAta: dsc_token_ata,
+        seller: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const after = await getAccount(provider.connection, asset_ata);
+    expect(after.amount.toString()).to.equal(before.amount.toString());
+    expect(
+      await indie_games_program.account.auction.fetchNullable(auction)
+    ).to.equal(null);
+  });
+
+  it("sells a dutch auction listing at its decayed price", async () => {
+    const listing = await list_asset(2, {
+      dutchAuction: {
+        startPrice: new BN(1_000),
+        floorPrice: new BN(500),
+        startTime: new BN(Math.floor(Date.now() / 1000) - 50),
+        decayDuration: new BN(100),
+        curve: { linear: {} },
+      },
+    });
+    // already halfway down the curve, so a buyer capped under the floor misses
+    let filled = true;
+    try {
+      await buy_listing(listing, 1, 400);
+    } catch (error) {
+      filled = false;
+    }
+    expect(filled).to.equal(false);
+
+    const buyer_before = await getAccount(provider.connection, buyer_dsc_ata);
+    await buy_listing(listing, 1, 1_000);
+    const buyer_after = await getAccount(provider.connection, buyer_dsc_ata);
+    const paid = Number(buyer_before.amount - buyer_after.amount);
+    expect(paid).to.be.lessThan(1_000);
+    expect(paid).to.be.at.least(500);
+
+    const before = await getAccount(provider.connection, asset_ata);
+    await indie_games_program.methods
+      .cancelListing()
+      .accountsStrict({
+        saleAcc: listing.sale_acc,
+        marketAssetAta: listing.escrow,
+        assetMint: asset_mint,
+        marketAssetAtaAuthority: listing.escrow_authority,
+        sellerAssetAta: asset_ata,
+        seller: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const after = await getAccount(provider.connection, asset_ata);
+    expect((after.amount - before.amount).toString()).to.equal("1");
+    expect(
+      await indie_games_program.account.sale.fetchNullable(listing.sale_acc)
+    ).to.equal(null);
+    expect(await provider.connection.getAccountInfo(listing.escrow)).to.equal(
+      null
+    );
+  });
+
+  it("fills a collection-wide offer from a holder", async () => {
+    const { offer, offer_vault, buyer_asset_ata } = await make_offer(
+      2,
+      Math.floor(Date.now() / 1000) + 60
+    );
+    const vault = await getAccount(provider.connection, offer_vault);
+    expect(vault.amount.toString()).to.equal("400");
+    const bought_before = await getAccount(
+      provider.connection,
+      buyer_asset_ata
+    );
+    await indie_games_program.methods
+      .acceptOffer({ quantity: new BN(2) })
+      .accountsStrict({
+        offer,
+        offerVault: offer_vault,
+        marketplace,
+        assetAccount: asset_data_account,
+        assetMint: asset_mint,
+        holderAssetAta: asset_ata,
+        holderAssetAtaAuthority: asset_ata_auth,
+        buyerAssetAta: buyer_asset_ata,
+        holderDscAta: dsc_token_ata,
+        buyerDscAta: buyer_dsc_ata,
+        feeTreasuryAta: fee_treasury_ata,
+        royaltyAta: dsc_token_ata,
+        buyer: buyer.publicKey,
+        holder: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const bought_after = await getAccount(provider.connection, buyer_asset_ata);
+    expect((bought_after.amount - bought_before.amount).toString()).to.equal(
+      "2"
+    );
+    expect(
+      await indie_games_program.account.offer.fetchNullable(offer)
+    ).to.equal(null);
+    expect(await provider.connection.getAccountInfo(offer_vault)).to.equal(
+      null
+    );
+  });
+
+  it("refunds cancelled and expired offers", async () => {
+    const before = await getAccount(provider.connection, buyer_dsc_ata);
+    const cancelled = await make_offer(1, Math.floor(Date.now() / 1000) + 60);
+    await indie_games_program.methods
+      .cancelOffer()
+      .accountsStrict({
+        offer: cancelled.offer,
+        offerVault: cancelled.offer_vault,
+        buyerDscAta: buyer_dsc_ata,
+        buyer: buyer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([buyer])
+      .rpc();
+    expect(
+      await indie_games_program.account.offer.fetchNullable(cancelled.offer)
+    ).to.equal(null);
+
+    const expiring = await make_offer(3, Math.floor(Date.now() / 1000) + 3);
+    await sleep(5_000);
+    // anyone can crank an expired offer, the refund still goes to the buyer
+    await indie_games_program.methods
+      .expireOffer()
+      .accountsStrict({
+        offer: expiring.offer,
+        offerVault: expiring.offer_vault,
+        buyerDscAta: buyer_dsc_ata,
+        buyer: buyer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const after = await getAccount(provider.connection, buyer_dsc_ata);
+    expect(after.amount.toString()).to.equal(before.amount.toString());
+    expect(
+      await indie_games_program.account.offer.fetchNullable(expiring.offer)
+    ).to.equal(null);
+  });
+
+  it("swaps an asset bundle for DSC", async () => {
+    const { swap, swap_vault, escrow } = await open_swap(50, 300);
+    const buyer_asset_ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        asset_mint,
+        buyer.publicKey
+      )
+    ).address;
+    const assets_before = await getAccount(
+      provider.connection,
+      buyer_asset_ata
+    );
+    const dsc_before = await getAccount(provider.connection, buyer_dsc_ata);
+    await indie_games_program.methods
+      .acceptSwap()
+      .accountsStrict({
+        swap,
+        swapVault: swap_vault,
+        initiator: signer,
+        initiatorDscAta: dsc_token_ata,
+        counterpartyDscAta: buyer_dsc_ata,
+        counterparty: buyer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: asset_data_account, isSigner: false, isWritable: false },
+        { pubkey: asset_mint, isSigner: false, isWritable: false },
+        { pubkey: escrow, isSigner: false, isWritable: true },
+        { pubkey: buyer_asset_ata, isSigner: false, isWritable: true },
+      ])
+      .signers([buyer])
+      .rpc();
+    const assets_after = await getAccount(provider.connection, buyer_asset_ata);
+    expect((assets_after.amount - assets_before.amount).toString()).to.equal(
+      "1"
+    );
+    // pays 300 for the bundle and takes the 50 DSC that came with it
+    const dsc_after = await getAccount(provider.connection, buyer_dsc_ata);
+    expect((dsc_before.amount - dsc_after.amount).toString()).to.equal("250");
+    expect(
+      await indie_games_program.account.swap.fetchNullable(swap)
+    ).to.equal(null);
+    expect(await provider.connection.getAccountInfo(escrow)).to.equal(null);
+    expect(await provider.connection.getAccountInfo(swap_vault)).to.equal(
+      null
+    );
+  });
+
+  it("cancels a swap back to the initiator", async () => {
+    const assets_before = await getAccount(provider.connection, asset_ata);
+    const { swap, swap_vault, escrow } = await open_swap(20, 100);
+    await indie_games_program.methods
+      .cancelSwap()
+      .accountsStrict({
+        swap,
+        swapVault: swap_vault,
+        initiator: signer,
+        initiatorDscAta: dsc_token_ata,
+        signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: asset_mint, isSigner: false, isWritable: false },
+        { pubkey: escrow, isSigner: false, isWritable: true },
+        { pubkey: asset_ata, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+    const assets_after = await getAccount(provider.connection, asset_ata);
+    expect(assets_after.amount.toString()).to.equal(
+      assets_before.amount.toString()
+    );
+    expect(
+      await indie_games_program.account.swap.fetchNullable(swap)
+    ).to.equal(null);
+    expect(await provider.connection.getAccountInfo(escrow)).to.equal(null);
+  });
+
+  it("returns an expired listing to its seller", async () => {
+    const listing = await list_asset(1, {
+      expiresAt: Math.floor(Date.now() / 1000) + 3,
+    });
+    const expire = () =>
+      indie_games_program.methods
+        .expireListing()
+        .accountsStrict({
+          saleAcc: listing.sale_acc,
+          marketAssetAta: listing.escrow,
+          assetMint: asset_mint,
+          marketAssetAtaAuthority: listing.escrow_authority,
+          sellerAssetAta: asset_ata,
+          seller: signer,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    let expired = true;
+    try {
+      await expire();
+    } catch (error) {
+      expired = false;
+    }
+    expect(expired).to.equal(false);
+
+    await sleep(5_000);
+    let filled = true;
+    try {
+      await buy_listing(listing, 1, 1_000);
+    } catch (error) {
+      filled = false;
+    }
+    expect(filled).to.equal(false);
+
+    // once past its deadline the escrowed asset and rent go back to the seller
+    const before = await getAccount(provider.connection, asset_ata);
+    await expire();
+    const after = await getAccount(provider.connection, asset_ata);
+    expect((after.amount - before.amount).toString()).to.equal("1");
+    expect(
+      await indie_games_program.account.sale.fetchNullable(listing.sale_acc)
+    ).to.equal(null);
+    expect(await provider.connection.getAccountInfo(listing.escrow)).to.equal(
+      null
+    );
+  });
+
+  it("settles a native SOL listing once the venue allows it", async () => {
+    const sol_terms = {
+      salePrice: LAMPORTS_PER_SOL / 10,
+      paymentMint: SystemProgram.programId,
+    };
+    let listed = true;
+    try {
+      await list_asset(1, sol_terms);
+    } catch (error) {
+      listed = false;
+    }
+    expect(listed).to.equal(false);
+
+    await indie_games_program.methods
+      .updateMarketplaceConfig({
+        feeBps: 100,
+        feeTreasury: fee_treasury.publicKey,
+        allowedPaymentMints: [dsc_mint, SystemProgram.programId],
+        minPrice: new BN(1),
+        maxPrice: new BN(0),
+        allowForeignAssets: false,
+        oracleFloorBps: 0,
+      })
+      .accountsStrict({ marketplace, admin: signer })
+      .rpc();
+    const listing = await list_asset(1, sol_terms);
+    const buyer_before = await provider.connection.getBalance(
+      buyer.publicKey
+    );
+    const treasury_before = await provider.connection.getBalance(
+      fee_treasury.publicKey
+    );
+    await buy_listing(listing, 1, LAMPORTS_PER_SOL / 10, true);
+    // the provider wallet pays the transaction fee, the buyer only the price
+    const buyer_after = await provider.connection.getBalance(buyer.publicKey);
+    expect(buyer_before - buyer_after).to.equal(LAMPORTS_PER_SOL / 10);
+    const treasury_after = await provider.connection.getBalance(
+      fee_treasury.publicKey
+    );
+    expect(treasury_after - treasury_before).to.equal(LAMPORTS_PER_SOL / 1000);
+    expect(
+      await indie_games_program.account.sale.fetchNullable(listing.sale_acc)
+    ).to.equal(null);
+    expect(await provider.connection.getAccountInfo(listing.escrow)).to.equal(
+      null
+    );
+  });
+
+  it("keeps soulbound assets burnable and claimable by the game", async () => {
+    const badge = await create_asset(
+      "badge",
+      { tradeOption: false },
+      TOKEN_2022_PROGRAM_ID
+    );
+    const holding = await mint_holding(
+      "badge",
+      badge,
+      3,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const mint_info = await getMint(
+      provider.connection,
+      badge.mint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(getNonTransferable(mint_info)).to.not.equal(null);
+    // the mint PDA is the permanent delegate the game claws back through
+    expect(getPermanentDelegate(mint_info).delegate.toString()).to.equal(
+      badge.mint.toString()
+    );
+
+    await indie_games_program.methods
+      .burnAsset({
+        assetName: "badge",
+        assetGameId: game_acc,
+        amount: new BN(1),
+      })
+      .accountsStrict({
+        mint: badge.mint,
+        assetAccount: badge.account,
+        holderAta: holding.ata,
+        holderAtaAuthority: holding.authority,
+        collateralVault: null,
+        holderDscAta: null,
+        priceFeed: null,
+        vaultAuthority: dsc_vault_authority,
+        holder: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    await indie_games_program.methods
+      .clawbackAsset({ assetName: "badge", amount: new BN(1) })
+      .accountsStrict({
+        mint: badge.mint,
+        assetAccount: badge.account,
+        holderAta: holding.ata,
+        gameAccount: game_acc,
+        owner: signer,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    const left = await getAccount(
+      provider.connection,
+      holding.ata,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(left.amount.toString()).to.equal("1");
+  });
+
+  it("redeems locked collateral when burning backed assets", async () => {
+    // priced at 10 DSC and backed at 10%, so each token locks 1 DSC
+    const backed = await create_asset(
+      "backed",
+      { collateralOption: true, collateralRatio: 1_000 },
+      TOKEN_PROGRAM_ID
+    );
+    const collateral_vault = await open_collateral_vault(
+      "backed",
+      backed.account
+    );
+    await mint_dsc(5_000_000);
+    const dsc_before = await getAccount(provider.connection, dsc_token_ata);
+    const holding = await mint_holding(
+      "backed",
+      backed,
+      4,
+      TOKEN_PROGRAM_ID,
+      collateral_vault
+    );
+    let vault = await getAccount(provider.connection, collateral_vault);
+    expect(vault.amount.toString()).to.equal("4000000");
+    let dsc_after = await getAccount(provider.connection, dsc_token_ata);
+    expect((dsc_before.amount - dsc_after.amount).toString()).to.equal(
+      "4000000"
+    );
+
+    await indie_games_program.methods
+      .burnAsset({
+        assetName: "backed",
+        assetGameId: game_acc,
+        amount: new BN(2),
+      })
+      .accountsStrict({
+        mint: backed.mint,
+        assetAccount: backed.account,
+        holderAta: holding.ata,
+        holderAtaAuthority: holding.authority,
+        collateralVault: collateral_vault,
+        holderDscAta: dsc_token_ata,
+        priceFeed: null,
+        vaultAuthority: dsc_vault_authority,
+        holder: signer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        assetTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    vault = await getAccount(provider.connection, collateral_vault);
+    expect(vault.amount.toString()).to.equal("2000000");
+    dsc_after = await getAccount(provider.connection, dsc_token_ata);
+    expect((dsc_before.amount - dsc_after.amount).toString()).to.equal(
+      "2000000"
+    );
+    const left = await getAccount(provider.connection, holding.ata);
+    expect(left.amount.toString()).to.equal("2");
+    const backed_acc = await indie_games_program.account.assetData.fetch(
+      backed.account
+    );
+    expect(backed_acc.totalCollateral.toString()).to.equal("2000000");
+    expect(backed_acc.collateralizedSupply.toString()).to.equal("2");
+  });
+
+  it("books backing in a per-asset vault clients can query", async () => {
+    const [backed_account] = PublicKey.findProgramAddressSync(
+      [Buffer.from("backed"), game_acc.toBuffer()],
+      indie_games_program.programId
+    );
+    const [backed_mint] = PublicKey.findProgramAddressSync(
+      [game_acc.toBuffer(), backed_account.toBuffer()],
+      indie_games_program.programId
+    );
+    const [collateral_vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_vault"), backed_account.toBuffer()],
+      indie_games_program.programId
+    );
+    const vault = await getAccount(provider.connection, collateral_vault);
+    expect(vault.owner.toString()).to.equal(dsc_vault_authority.toString());
+    expect(vault.mint.toString()).to.equal(dsc_mint.toString());
+
+    // backed assets can't be minted without paying into their vault
+    let minted = true;
+    try {
+      await mint_holding(
+        "backed",
+        { account: backed_account, mint: backed_mint },
+        1,
+        TOKEN_PROGRAM_ID
+      );
+    } catch (error) {
+      minted = false;
+    }
+    expect(minted).to.equal(false);
+    // and unbacked ones get no vault
+    let opened = true;
+    try {
+      await open_collateral_vault(asset.name, asset_data_account);
+    } catch (error) {
+      opened = false;
+    }
+    expect(opened).to.equal(false);
+
+    const status = await indie_games_program.methods
+      .getCollateralStatus({ assetName: "backed", assetGameId: game_acc })
+      .accountsStrict({
+        assetAccount: backed_account,
+        collateralVault: collateral_vault,
+      })
+      .view();
+    expect(status.totalCollateral.toString()).to.equal("2000000");
+    expect(status.collateralizedSupply.toString()).to.equal("2");
+    expect(status.collateralPerToken.toString()).to.equal("1000000");
+    expect(status.vaultBalance.toString()).to.equal("2000000");
+  });
+
+  it("liquidates an asset whose backing falls behind its price", async () => {
+    const levered = await create_asset(
+      "levered",
+      { collateralOption: true, collateralRatio: 15_000 },
+      TOKEN_PROGRAM_ID
+    );
+    const collateral_vault = await open_collateral_vault(
+      "levered",
+      levered.account
+    );
+    const [levered_feed] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("price_feed"),
+        levered.account.toBuffer(),
+        signer.toBuffer(),
+      ],
+      stable_coin_program.programId
+    );
+    await stable_coin_program.methods
+      .initializePriceFeed({
+        subject: levered.account,
+        updaters: [signer],
+        maxStaleness: new BN(60),
+      })
+      .accountsStrict({
+        priceFeed: levered_feed,
+        authority: signer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await indie_games_program.methods
+      .setAssetPriceFeed({ assetName: "levered" })
+      .accountsStrict({
+        priceFeed: levered_feed,
+        assetAccount: levered.account,
+        gameAccount: game_acc,
+        authority: signer,
+      })
+      .rpc();
+    const push_levered_price = (price: number, confidence: number) =>
+      stable_coin_program.methods
+        .pushPrice({ price: new BN(price), confidence: new BN(confidence) })
+        .accountsStrict({ priceFeed: levered_feed, updater: signer })
+        .rpc();
+    const refresh_health = () =>
+      indie_games_program.methods
+        .refreshAssetHealth({ assetName: "levered", assetGameId: game_acc })
+        .accountsStrict({
+          assetAccount: levered.account,
+          priceFeed: levered_feed,
+        })
+        .rpc();
+    // backed at 150% of 1 DSC a token
+    await mint_dsc(9_000_000);
+    await push_levered_price(1_000_000, 0);
+    const holding = await mint_holding(
+      "levered",
+      levered,
+      4,
+      TOKEN_PROGRAM_ID,
+      collateral_vault,
+      levered_feed
+    );
+    const liquidate = (repay_amount: number, burn_amount: number) =>
+      indie_games_program.methods
+        .liquidateAssetCollateral({
+          assetName: "levered",
+          assetGameId: game_acc,
+          repayAmount: new BN(repay_amount),
+          burnAmount: new BN(burn_amount),
+        })
+        .accountsStrict({
+          mint: levered.mint,
+          assetAccount: levered.account,
+          liquidatorAta: holding.ata,
+          liquidatorAtaAuthority: holding.authority,
+          collateralVault: collateral_vault,
+          liquidatorDscAta: dsc_token_ata,
+          priceFeed: levered_feed,
+          vaultAuthority: dsc_vault_authority,
+          liquidator: signer,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          assetTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    let liquidated = true;
+    try {
+      await liquidate(0, 1);
+    } catch (error) {
+      liquidated = false;
+    }
+    expect(liquidated).to.equal(false);
+
+    // the price doubles and the 6 DSC in the vault back only half the supply
+    await push_levered_price(2_000_000, 0);
+    await refresh_health();
+    let levered_acc = await indie_games_program.account.assetData.fetch(
+      levered.account
+    );
+    expect(levered_acc.unhealthy).to.equal(true);
+    let minted = true;
+    try {
+      await mint_holding(
+        "levered",
+        levered,
+        1,
+        TOKEN_PROGRAM_ID,
+        collateral_vault,
+        levered_feed
+      );
+    } catch (error) {
+      minted = false;
+    }
+    expect(minted).to.equal(false);
+
+    // burning pays the oracle value plus the 5% bonus out of the vault
+    let dsc_before = await getAccount(provider.connection, dsc_token_ata);
+    await liquidate(0, 2);
+    let dsc_after = await getAccount(provider.connection, dsc_token_ata);
+    expect((dsc_after.amount - dsc_before.amount).toString()).to.equal(
+      "4200000"
+    );
+    levered_acc = await indie_games_program.account.assetData.fetch(
+      levered.account
+    );
+    expect(levered_acc.unhealthy).to.equal(true);
+
+    // repaying takes only the 4.2 DSC shortfall and lifts the pause
+    dsc_before = dsc_after;
+    await liquidate(5_000_000, 0);
+    dsc_after = await getAccount(provider.connection, dsc_token_ata);
+    expect((dsc_before.amount - dsc_after.amount).toString()).to.equal(
+      "4200000"
+    );
+    const tokens = await getAccount(provider.connection, holding.ata);
+    expect(tokens.amount.toString()).to.equal("2");
+    levered_acc = await indie_games_program.account.assetData.fetch(
+      levered.account
+    );
+    expect(levered_acc.totalCollateral.toString()).to.equal("6000000");
+    expect(levered_acc.collateralizedSupply.toString()).to.equal("2");
+    expect(levered_acc.unhealthy).to.equal(false);
+  });
 });